@@ -0,0 +1,366 @@
+#[cfg(feature = "futures")]
+use futures::Stream;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Mutex;
+use std::task::{Context, Poll, Waker};
+
+struct Inner<T> {
+    fresh: bool,
+    val: T,
+    version: u64,
+    dropped: u64,
+    wakers: Vec<Waker>
+}
+
+/// Tracked value that can be awaited for its next change.
+///
+/// Any number of tasks can await `next_change` concurrently; all of them are
+/// woken by the next set that changes the value.
+pub struct AsyncTracked<T> {
+    inner: Mutex<Inner<T>>
+}
+
+impl<T: Clone + PartialEq> AsyncTracked<T> {
+    pub fn new(val: T) -> Self {
+        AsyncTracked {
+            inner: Mutex::new(Inner {
+                fresh: true,
+                val,
+                version: 0,
+                dropped: 0,
+                wakers: Vec::new()
+            })
+        }
+    }
+
+    /// Set a new value, marked as fresh if not equal to the existing value.
+    ///
+    /// Wakes every task awaiting a change.
+    pub fn set(&self, val: T) {
+        let mut inner = self.inner.lock().unwrap();
+
+        if inner.val != val {
+            if inner.fresh {
+                inner.dropped = inner.dropped.wrapping_add(1);
+            }
+
+            inner.val = val;
+            inner.fresh = true;
+            inner.version = inner.version.wrapping_add(1);
+
+            for waker in inner.wakers.drain(..) {
+                waker.wake();
+            }
+        }
+    }
+
+    /// Get the current value, marking it as stale.
+    pub fn get(&self) -> T {
+        let mut inner = self.inner.lock().unwrap();
+
+        inner.fresh = false;
+        inner.val.clone()
+    }
+
+    /// Get the current value if it's been modified since last time we checked, marking it as stale.
+    pub fn get_if_fresh(&self) -> Option<T> {
+        let mut inner = self.inner.lock().unwrap();
+
+        if inner.fresh {
+            inner.fresh = false;
+            Some(inner.val.clone())
+        } else {
+            None
+        }
+    }
+
+    // Get the current value without marking it.
+    pub fn peek(&self) -> T {
+        self.inner.lock().unwrap().val.clone()
+    }
+
+    pub fn is_fresh(&self) -> bool {
+        self.inner.lock().unwrap().fresh
+    }
+
+    /// Number of value-changing sets that overwrote a fresh value before it was read.
+    pub fn dropped_changes(&self) -> u64 {
+        self.inner.lock().unwrap().dropped
+    }
+
+    /// Number of value-changing sets so far.
+    pub fn generation_count(&self) -> u64 {
+        self.inner.lock().unwrap().version
+    }
+
+    /// Stream of generations, yielding each generation reached after the stream was created.
+    ///
+    /// Every generation is yielded in order, even if several changes happened between polls;
+    /// only the value itself is coalesced, so fetch it with `peek` when needed. The stream never
+    /// ends.
+    #[cfg(feature = "futures")]
+    pub fn generation_stream(&self) -> GenerationStream<'_, T> {
+        GenerationStream {
+            tracked: self,
+            last: self.generation_count()
+        }
+    }
+
+    /// Wait for the next set that changes the value, resolving to the new value.
+    ///
+    /// Changes made after this call but before the future is first polled still count.
+    /// Freshness is not affected.
+    pub fn next_change(&self) -> NextChange<'_, T> {
+        NextChange {
+            tracked: self,
+            version: self.inner.lock().unwrap().version
+        }
+    }
+}
+
+/// Future returned by `AsyncTracked::next_change`.
+pub struct NextChange<'a, T: 'a> {
+    tracked: &'a AsyncTracked<T>,
+    version: u64
+}
+
+impl<'a, T: Clone> Future for NextChange<'a, T> {
+    type Output = T;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<T> {
+        let mut inner = self.tracked.inner.lock().unwrap();
+
+        if inner.version != self.version {
+            Poll::Ready(inner.val.clone())
+        } else {
+            if !inner.wakers.iter().any(|w| w.will_wake(cx.waker())) {
+                inner.wakers.push(cx.waker().clone());
+            }
+
+            Poll::Pending
+        }
+    }
+}
+
+/// Stream returned by `AsyncTracked::generation_stream`.
+#[cfg(feature = "futures")]
+pub struct GenerationStream<'a, T: 'a> {
+    tracked: &'a AsyncTracked<T>,
+    last: u64
+}
+
+#[cfg(feature = "futures")]
+impl<'a, T> Stream for GenerationStream<'a, T> {
+    type Item = u64;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<u64>> {
+        {
+            let mut inner = self.tracked.inner.lock().unwrap();
+
+            if inner.version == self.last {
+                if !inner.wakers.iter().any(|w| w.will_wake(cx.waker())) {
+                    inner.wakers.push(cx.waker().clone());
+                }
+
+                return Poll::Pending;
+            }
+        }
+
+        self.last = self.last.wrapping_add(1);
+        Poll::Ready(Some(self.last))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Barrier};
+    use std::task::Wake;
+    use std::thread;
+
+    struct CountingWaker(AtomicUsize);
+
+    impl Wake for CountingWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    struct ThreadWaker(thread::Thread);
+
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    fn block_on<F: Future>(fut: F) -> F::Output {
+        let mut fut = Box::pin(fut);
+        let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+        let mut cx = Context::from_waker(&waker);
+
+        loop {
+            match fut.as_mut().poll(&mut cx) {
+                Poll::Ready(val) => return val,
+                Poll::Pending => thread::park()
+            }
+        }
+    }
+
+    #[test]
+    fn test_new() {
+        let t = AsyncTracked::new(5);
+
+        assert!(t.is_fresh());
+        assert_eq!(t.peek(), 5);
+    }
+
+    #[test]
+    fn test_get_if_fresh() {
+        let t = AsyncTracked::new(5);
+
+        assert_eq!(t.get_if_fresh(), Some(5));
+        assert!(t.get_if_fresh().is_none());
+
+        t.set(6);
+
+        assert_eq!(t.get_if_fresh(), Some(6));
+    }
+
+    #[test]
+    fn test_dropped_changes() {
+        let t = AsyncTracked::new(5);
+
+        // Initial value overwritten before being read
+        t.set(6);
+        t.set(7);
+        t.set(7);
+
+        assert_eq!(t.dropped_changes(), 2);
+
+        t.get();
+        t.set(8);
+
+        assert_eq!(t.dropped_changes(), 2);
+    }
+
+    #[test]
+    fn test_next_change_wakes_all() {
+        let t = AsyncTracked::new(5);
+        let counter = Arc::new(CountingWaker(AtomicUsize::new(0)));
+        let waker = Waker::from(counter.clone());
+        let mut cx = Context::from_waker(&waker);
+
+        let mut a = Box::pin(t.next_change());
+        let mut b = Box::pin(t.next_change());
+
+        assert!(a.as_mut().poll(&mut cx).is_pending());
+        assert!(b.as_mut().poll(&mut cx).is_pending());
+
+        // Same value - nobody is woken
+        t.set(5);
+
+        assert_eq!(counter.0.load(Ordering::SeqCst), 0);
+        assert!(a.as_mut().poll(&mut cx).is_pending());
+
+        // Different value - all awaiters resolve
+        t.set(6);
+
+        assert!(counter.0.load(Ordering::SeqCst) > 0);
+        assert_eq!(a.as_mut().poll(&mut cx), Poll::Ready(6));
+        assert_eq!(b.as_mut().poll(&mut cx), Poll::Ready(6));
+    }
+
+    #[test]
+    fn test_next_change_across_threads() {
+        let t = Arc::new(AsyncTracked::new(0));
+        let barrier = Arc::new(Barrier::new(4));
+
+        let handles: Vec<_> = (0..3).map(|_| {
+            let t = t.clone();
+            let barrier = barrier.clone();
+
+            thread::spawn(move || {
+                let change = t.next_change();
+                barrier.wait();
+                block_on(change)
+            })
+        }).collect();
+
+        barrier.wait();
+        t.set(42);
+
+        for handle in handles {
+            assert_eq!(handle.join().unwrap(), 42);
+        }
+    }
+
+    #[test]
+    fn test_generation_count() {
+        let t = AsyncTracked::new(5);
+
+        t.set(5);
+        t.set(6);
+        t.set(7);
+
+        assert_eq!(t.generation_count(), 2);
+    }
+
+    #[test]
+    #[cfg(feature = "futures")]
+    fn test_generation_stream() {
+        use futures::executor::block_on;
+        use futures::{FutureExt, StreamExt};
+
+        let t = AsyncTracked::new(0);
+
+        t.set(1);
+
+        // Only changes after the stream was created are yielded
+        let mut generations = t.generation_stream();
+
+        assert!(generations.next().now_or_never().is_none());
+
+        t.set(2);
+
+        assert_eq!(block_on(generations.next()), Some(2));
+
+        // Not coalesced while not polled
+        t.set(3);
+        t.set(4);
+
+        assert_eq!(block_on(generations.next()), Some(3));
+        assert_eq!(block_on(generations.next()), Some(4));
+        assert!(generations.next().now_or_never().is_none());
+    }
+
+    #[test]
+    #[cfg(feature = "futures")]
+    fn test_generation_stream_across_threads() {
+        use futures::executor::block_on;
+        use futures::StreamExt;
+
+        let t = Arc::new(AsyncTracked::new(0));
+        let producer = t.clone();
+        let mut generations = t.generation_stream();
+
+        let handle = thread::spawn(move || {
+            for v in 1..=3 {
+                producer.set(v);
+            }
+        });
+
+        let mut last = 0;
+
+        while last < 3 {
+            let generation = block_on(generations.next()).unwrap();
+
+            assert_eq!(generation, last + 1);
+            last = generation;
+        }
+
+        handle.join().unwrap();
+    }
+}
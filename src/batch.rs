@@ -0,0 +1,159 @@
+use tracked_value::TrackedValue;
+
+/// Compact set of bits, one per value, as collected by `collect_freshness`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BitSet {
+    words: Vec<u64>,
+    len: usize
+}
+
+impl BitSet {
+    fn new(len: usize) -> Self {
+        BitSet {
+            words: vec![0; len.div_ceil(64)],
+            len
+        }
+    }
+
+    fn insert(&mut self, index: usize) {
+        self.words[index / 64] |= 1 << (index % 64);
+    }
+
+    /// Whether the bit at `index` is set. Out of range bits are never set.
+    pub fn contains(&self, index: usize) -> bool {
+        index < self.len && self.words[index / 64] & (1 << (index % 64)) != 0
+    }
+
+    /// Number of bits in the set, set or not.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Number of set bits.
+    pub fn count_ones(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    /// The bits packed into words, least significant bit first.
+    pub fn words(&self) -> &[u64] {
+        &self.words
+    }
+}
+
+/// Collect the freshness of each value into a bitset, without marking them.
+///
+/// Bit `i` is set if `values[i]` is fresh.
+pub fn collect_freshness(values: &[&dyn TrackedValue]) -> BitSet {
+    let mut bits = BitSet::new(values.len());
+
+    for (i, value) in values.iter().enumerate() {
+        if value.is_fresh() {
+            bits.insert(i);
+        }
+    }
+
+    bits
+}
+
+/// Collect the freshness of each value into a bitset, marking them all as stale.
+///
+/// Bit `i` is set if `values[i]` was fresh.
+pub fn collect_and_consume(values: &mut [&mut dyn TrackedValue]) -> BitSet {
+    let mut bits = BitSet::new(values.len());
+
+    for (i, value) in values.iter_mut().enumerate() {
+        if value.consume() {
+            bits.insert(i);
+        }
+    }
+
+    bits
+}
+
+/// Count how many values are fresh, without marking them.
+pub fn count_fresh(values: &[&dyn TrackedValue]) -> usize {
+    values.iter().filter(|value| value.is_fresh()).count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reference::TrackedRef;
+    use value::Tracked;
+
+    #[test]
+    fn test_collect_freshness() {
+        let a = Tracked::new(1);
+        let mut b = TrackedRef::new("b");
+        let c = Tracked::new(3.0);
+
+        // Mark b as stale
+        b.get();
+
+        let bits = collect_freshness(&[&a, &b, &c]);
+
+        assert_eq!(bits.len(), 3);
+        assert!(bits.contains(0));
+        assert!(!bits.contains(1));
+        assert!(bits.contains(2));
+        assert!(!bits.contains(3));
+        assert_eq!(bits.words(), &[0b101]);
+
+        // Freshness untouched
+        assert!(a.is_fresh());
+        assert!(c.is_fresh());
+    }
+
+    #[test]
+    fn test_collect_freshness_many() {
+        let mut values: Vec<_> = (0..70).map(Tracked::new).collect();
+
+        values[1].get();
+        values[65].get();
+
+        let refs: Vec<&dyn TrackedValue> = values.iter().map(|v| v as &dyn TrackedValue).collect();
+        let bits = collect_freshness(&refs);
+
+        assert_eq!(bits.words().len(), 2);
+        assert_eq!(bits.count_ones(), 68);
+        assert!(!bits.contains(1));
+        assert!(bits.contains(64));
+        assert!(!bits.contains(65));
+    }
+
+    #[test]
+    fn test_collect_and_consume() {
+        let mut a = Tracked::new(1);
+        let mut b = TrackedRef::new("b");
+
+        // Mark a as stale
+        a.get();
+
+        let bits = collect_and_consume(&mut [&mut a, &mut b]);
+
+        assert_eq!(bits.words(), &[0b10]);
+        assert!(!a.is_fresh());
+        assert!(!b.is_fresh());
+    }
+
+    #[test]
+    fn test_count_fresh() {
+        let a = Tracked::new(1);
+        let mut b = TrackedRef::new("b");
+        let c = TrackedRef::new(vec![3]);
+
+        // Mark b as stale
+        b.get();
+
+        assert_eq!(count_fresh(&[&a, &b, &c]), 2);
+        assert_eq!(count_fresh(&[]), 0);
+
+        // Freshness untouched
+        assert!(a.is_fresh());
+        assert!(c.is_fresh());
+    }
+}
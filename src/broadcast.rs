@@ -0,0 +1,103 @@
+use reference::{TrackedRef, TrackedRefSet};
+
+/// Identifies a mirror registered with a `Broadcast`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct MirrorId(usize);
+
+/// Tracked value fanned out to any number of mirrors.
+///
+/// Setting the master value propagates it to every mirror, and each mirror
+/// tracks freshness independently for its own consumer.
+pub struct Broadcast<T> {
+    val: T,
+    mirrors: Vec<TrackedRef<T>>
+}
+
+impl<T: Clone + PartialEq> Broadcast<T> {
+    pub fn new(val: T) -> Self {
+        Broadcast {
+            val,
+            mirrors: Vec::new()
+        }
+    }
+
+    /// Register a new mirror, which starts out fresh with the current value.
+    pub fn register(&mut self) -> MirrorId {
+        self.mirrors.push(TrackedRef::new(self.val.clone()));
+        MirrorId(self.mirrors.len() - 1)
+    }
+
+    /// Set a new value, marking each mirror as fresh if not equal to the existing value.
+    pub fn set(&mut self, val: T) {
+        if self.val != val {
+            for mirror in &mut self.mirrors {
+                mirror.set(val.clone());
+            }
+
+            self.val = val;
+        }
+    }
+
+    /// Get the value for a mirror if it's been modified since that mirror last checked, marking
+    /// it as stale for that mirror only.
+    pub fn mirror_get_if_fresh(&mut self, id: MirrorId) -> Option<&T> {
+        self.mirrors[id.0].get_if_fresh()
+    }
+
+    pub fn mirror(&self, id: MirrorId) -> &TrackedRef<T> {
+        &self.mirrors[id.0]
+    }
+
+    // Get the current value without marking any mirror.
+    pub fn peek(&self) -> &T {
+        &self.val
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_register() {
+        let mut b = Broadcast::new(5);
+        let m = b.register();
+
+        assert!(b.mirror(m).is_fresh());
+        assert_eq!(b.mirror_get_if_fresh(m), Some(&5));
+    }
+
+    #[test]
+    fn test_independent_mirrors() {
+        let mut b = Broadcast::new(5);
+        let m1 = b.register();
+        let m2 = b.register();
+
+        // Both consume the initial value
+        b.mirror_get_if_fresh(m1);
+        b.mirror_get_if_fresh(m2);
+
+        b.set(6);
+
+        assert!(b.mirror(m1).is_fresh());
+        assert!(b.mirror(m2).is_fresh());
+
+        // First mirror consumes - second is still fresh
+        assert_eq!(b.mirror_get_if_fresh(m1), Some(&6));
+        assert!(b.mirror_get_if_fresh(m1).is_none());
+        assert!(b.mirror(m2).is_fresh());
+        assert_eq!(b.mirror_get_if_fresh(m2), Some(&6));
+    }
+
+    #[test]
+    fn test_set_same_val() {
+        let mut b = Broadcast::new(5);
+        let m = b.register();
+
+        b.mirror_get_if_fresh(m);
+        b.set(5);
+
+        assert!(!b.mirror(m).is_fresh());
+        assert_eq!(b.peek(), &5);
+    }
+}
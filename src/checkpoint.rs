@@ -0,0 +1,242 @@
+use reference::{Snapshot, TrackedRef};
+use std::any::Any;
+use std::marker::PhantomData;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static NEXT_GROUP_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// Registered value that can capture and restore its own state.
+trait Checkpointed {
+    fn snapshot(&mut self);
+    fn rollback(&mut self);
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+struct Member<'a, T: 'a> {
+    tracked: &'a mut TrackedRef<T>,
+    snap: Snapshot<T>
+}
+
+impl<'a, T: Clone + 'static> Checkpointed for Member<'a, T> {
+    fn snapshot(&mut self) {
+        self.snap = self.tracked.snapshot();
+    }
+
+    fn rollback(&mut self) {
+        self.tracked.restore(self.snap.clone());
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        &*self.tracked
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        &mut *self.tracked
+    }
+}
+
+/// Typed handle to a value registered with a `CheckpointGroup`.
+pub struct CheckpointHandle<T> {
+    group: usize,
+    index: usize,
+    marker: PhantomData<fn() -> T>
+}
+
+impl<T> Clone for CheckpointHandle<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for CheckpointHandle<T> {}
+
+/// Group of `TrackedRef` values that can be rolled back together.
+///
+/// Values of different types can be registered in the same group. Registered
+/// values are only reachable through the group while it is alive, using the
+/// handle returned by `register`. Call `rollback` to restore every value and
+/// its freshness to the last snapshot; committing is just dropping the group.
+pub struct CheckpointGroup<'a> {
+    id: usize,
+    members: Vec<Box<dyn Checkpointed + 'a>>
+}
+
+impl<'a> CheckpointGroup<'a> {
+    pub fn new() -> Self {
+        CheckpointGroup {
+            id: NEXT_GROUP_ID.fetch_add(1, Ordering::Relaxed),
+            members: Vec::new()
+        }
+    }
+
+    /// Add a value to the group, capturing its current state. Returns a handle to access it.
+    pub fn register<T: Clone + 'static>(&mut self, tracked: &'a mut TrackedRef<T>) -> CheckpointHandle<T> {
+        let snap = tracked.snapshot();
+
+        self.members.push(Box::new(Member { tracked, snap }));
+
+        CheckpointHandle {
+            group: self.id,
+            index: self.members.len() - 1,
+            marker: PhantomData
+        }
+    }
+
+    /// Get a registered value.
+    ///
+    /// Panics if the handle was returned by a different group.
+    pub fn get<T: 'static>(&self, handle: CheckpointHandle<T>) -> &TrackedRef<T> {
+        self.check_handle(&handle);
+        self.members[handle.index].as_any().downcast_ref().unwrap()
+    }
+
+    /// Get a registered value for modification.
+    ///
+    /// Panics if the handle was returned by a different group.
+    pub fn get_mut<T: 'static>(&mut self, handle: CheckpointHandle<T>) -> &mut TrackedRef<T> {
+        self.check_handle(&handle);
+        self.members[handle.index].as_any_mut().downcast_mut().unwrap()
+    }
+
+    fn check_handle<T>(&self, handle: &CheckpointHandle<T>) {
+        assert!(handle.group == self.id, "checkpoint handle belongs to a different group");
+    }
+
+    /// Capture the current state of every registered value.
+    pub fn snapshot(&mut self) {
+        for member in &mut self.members {
+            member.snapshot();
+        }
+    }
+
+    /// Restore every registered value and its freshness to the last snapshot.
+    pub fn rollback(&mut self) {
+        for member in &mut self.members {
+            member.rollback();
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.members.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty()
+    }
+}
+
+impl<'a> Default for CheckpointGroup<'a> {
+    fn default() -> Self {
+        CheckpointGroup::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reference::TrackedRefSet;
+
+    #[test]
+    fn test_rollback() {
+        let mut a = TrackedRef::new(1);
+        let mut b = TrackedRef::new(2);
+
+        // Mark a as stale, leave b fresh
+        a.get();
+
+        {
+            let mut group = CheckpointGroup::new();
+            let ia = group.register(&mut a);
+            let ib = group.register(&mut b);
+
+            group.get_mut(ia).set(10);
+            group.get_mut(ib).get();
+
+            assert!(group.get(ia).is_fresh());
+            assert!(!group.get(ib).is_fresh());
+
+            group.rollback();
+        }
+
+        assert_eq!(a.peek(), &1);
+        assert!(!a.is_fresh());
+        assert_eq!(b.peek(), &2);
+        assert!(b.is_fresh());
+    }
+
+    #[test]
+    fn test_commit() {
+        let mut a = TrackedRef::new(1);
+
+        {
+            let mut group = CheckpointGroup::new();
+            let ia = group.register(&mut a);
+
+            group.get_mut(ia).set(10);
+        }
+
+        assert_eq!(a.peek(), &10);
+    }
+
+    #[test]
+    fn test_snapshot() {
+        let mut a = TrackedRef::new(1);
+
+        {
+            let mut group = CheckpointGroup::new();
+            let ia = group.register(&mut a);
+
+            group.get_mut(ia).set(10);
+            group.snapshot();
+            group.get_mut(ia).set(20);
+            group.rollback();
+        }
+
+        // Rolled back to the latest snapshot only
+        assert_eq!(a.peek(), &10);
+    }
+
+    #[test]
+    fn test_rollback_mixed_types() {
+        let mut n = TrackedRef::new(1);
+        let mut s = TrackedRef::new(String::from("a"));
+
+        // Mark s as stale, leave n fresh
+        s.get();
+
+        {
+            let mut group = CheckpointGroup::new();
+            let hn = group.register(&mut n);
+            let hs = group.register(&mut s);
+
+            group.get_mut(hn).get();
+            group.get_mut(hs).set(String::from("b"));
+
+            assert_eq!(group.get(hs).peek(), "b");
+
+            group.rollback();
+        }
+
+        assert_eq!(n.peek(), &1);
+        assert!(n.is_fresh());
+        assert_eq!(s.peek(), "a");
+        assert!(!s.is_fresh());
+    }
+
+    #[test]
+    #[should_panic(expected = "different group")]
+    fn test_handle_from_other_group() {
+        let mut a = TrackedRef::new(1);
+        let mut b = TrackedRef::new(2);
+
+        let mut first = CheckpointGroup::new();
+        let mut second = CheckpointGroup::new();
+
+        first.register(&mut a);
+        let hb = second.register(&mut b);
+
+        // Same type and index, but from the other group
+        first.get(hb);
+    }
+}
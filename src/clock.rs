@@ -0,0 +1,69 @@
+use std::cell::Cell;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+/// Source of the current time for time-based wrappers.
+pub trait Clock {
+    fn now(&self) -> Instant;
+}
+
+/// Clock reading the system's monotonic time.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// Manually advanced clock for deterministic tests.
+///
+/// Clones share the same time, so a test can keep one and hand another to a wrapper.
+#[derive(Clone, Debug)]
+pub struct MockClock {
+    now: Rc<Cell<Instant>>
+}
+
+impl MockClock {
+    pub fn new() -> Self {
+        MockClock {
+            now: Rc::new(Cell::new(Instant::now()))
+        }
+    }
+
+    /// Move the time forward by `by`.
+    pub fn advance(&self, by: Duration) {
+        self.now.set(self.now.get() + by);
+    }
+}
+
+impl Default for MockClock {
+    fn default() -> Self {
+        MockClock::new()
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> Instant {
+        self.now.get()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mock_clock() {
+        let clock = MockClock::new();
+        let shared = clock.clone();
+        let start = clock.now();
+
+        assert_eq!(clock.now(), start);
+
+        shared.advance(Duration::from_millis(5));
+
+        assert_eq!(clock.now() - start, Duration::from_millis(5));
+    }
+}
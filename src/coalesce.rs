@@ -0,0 +1,159 @@
+/// Tracked value that merges sets between reads with a reducer.
+///
+/// Values set between two reads are folded into an accumulator. On read, the
+/// accumulated result is only reported if it differs from the last value that
+/// was returned, so a burst that nets back to the prior value stays stale.
+pub struct TrackedCoalesce<T> {
+    last: T,
+    acc: Option<T>,
+    reducer: Box<dyn Fn(T, T) -> T>,
+    dropped: u64
+}
+
+impl<T: PartialEq> TrackedCoalesce<T> {
+    /// Create a new wrapper around `val`, combining sets with `reducer`.
+    ///
+    /// The initial value counts as already read.
+    pub fn new<F>(val: T, reducer: F) -> Self
+        where F: Fn(T, T) -> T + 'static
+    {
+        TrackedCoalesce {
+            last: val,
+            acc: None,
+            reducer: Box::new(reducer),
+            dropped: 0
+        }
+    }
+
+    /// Fold a new value into the accumulator.
+    ///
+    /// The first set after a read starts a new accumulator.
+    pub fn set(&mut self, val: T)
+        where T: Clone
+    {
+        // Only kept while a fresh result is pending, to tell if this set replaces it
+        let pending = if self.is_fresh() { self.acc.clone() } else { None };
+
+        self.acc = Some(match self.acc.take() {
+            Some(acc) => (self.reducer)(acc, val),
+            None => val
+        });
+
+        if pending.is_some() && pending != self.acc {
+            self.dropped = self.dropped.wrapping_add(1);
+        }
+    }
+
+    /// Get the accumulated value if it differs from the last value returned,
+    /// resetting the accumulator either way.
+    pub fn get_if_fresh(&mut self) -> Option<&T> {
+        match self.acc.take() {
+            Some(acc) => {
+                if acc != self.last {
+                    self.last = acc;
+                    Some(&self.last)
+                } else {
+                    None
+                }
+            }
+            None => None
+        }
+    }
+
+    // Get the last returned value without touching the accumulator.
+    pub fn peek(&self) -> &T {
+        &self.last
+    }
+
+    pub fn is_fresh(&self) -> bool {
+        match self.acc {
+            Some(ref acc) => *acc != self.last,
+            None => false
+        }
+    }
+
+    /// Number of sets that changed an accumulated value that was fresh and never read.
+    pub fn dropped_changes(&self) -> u64 {
+        self.dropped
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new() {
+        let t = TrackedCoalesce::new(5, |a, b| a + b);
+
+        assert!(!t.is_fresh());
+        assert_eq!(t.peek(), &5);
+    }
+
+    #[test]
+    fn test_burst_nets_no_change() {
+        let mut t = TrackedCoalesce::new(0, |a, b| a + b);
+
+        // Burst of deltas that cancel out
+        t.set(3);
+        t.set(-2);
+        t.set(-1);
+
+        assert!(!t.is_fresh());
+        assert!(t.get_if_fresh().is_none());
+        assert_eq!(t.peek(), &0);
+    }
+
+    #[test]
+    fn test_burst_nets_change() {
+        let mut t = TrackedCoalesce::new(0, |a, b| a + b);
+
+        t.set(3);
+        t.set(2);
+
+        assert!(t.is_fresh());
+        assert_eq!(t.get_if_fresh(), Some(&5));
+
+        // Accumulator was reset by the read
+        assert!(!t.is_fresh());
+        assert!(t.get_if_fresh().is_none());
+    }
+
+    #[test]
+    fn test_latest_wins_reducer() {
+        let mut t = TrackedCoalesce::new("a", |_, b| b);
+
+        // Returns to the value last read - stays stale
+        t.set("b");
+        t.set("a");
+
+        assert!(t.get_if_fresh().is_none());
+
+        t.set("c");
+
+        assert_eq!(t.get_if_fresh(), Some(&"c"));
+    }
+
+    #[test]
+    fn test_dropped_changes() {
+        let mut t = TrackedCoalesce::new(0, |_, b| b);
+
+        // Same result - nothing is lost
+        t.set(1);
+        t.set(1);
+
+        assert_eq!(t.dropped_changes(), 0);
+
+        // 1 is overwritten before being read
+        t.set(2);
+
+        assert_eq!(t.dropped_changes(), 1);
+        assert_eq!(t.get_if_fresh(), Some(&2));
+
+        // Accumulator equal to the last read value isn't fresh, so nothing is lost
+        t.set(2);
+        t.set(3);
+
+        assert_eq!(t.dropped_changes(), 1);
+    }
+}
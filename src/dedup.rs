@@ -0,0 +1,65 @@
+/// Filters out consecutive repeats from a sequence of values.
+///
+/// Uses the same comparison as `Tracked::set`, but without any read-based
+/// freshness: every value that differs from the previously emitted one is
+/// passed straight through.
+#[derive(Clone)]
+pub struct Deduper<T> {
+    last: Option<T>
+}
+
+impl<T: Clone + PartialEq> Deduper<T> {
+    pub fn new() -> Self {
+        Deduper {
+            last: None
+        }
+    }
+
+    /// Feed the next value, returning it if it differs from the last value emitted.
+    ///
+    /// The first value fed is always emitted.
+    pub fn feed(&mut self, val: T) -> Option<T> {
+        if self.last.as_ref() == Some(&val) {
+            None
+        } else {
+            self.last = Some(val.clone());
+            Some(val)
+        }
+    }
+
+    /// The last value emitted, if any.
+    pub fn last(&self) -> Option<&T> {
+        self.last.as_ref()
+    }
+}
+
+impl<T: Clone + PartialEq> Default for Deduper<T> {
+    fn default() -> Self {
+        Deduper::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new() {
+        let d: Deduper<i32> = Deduper::new();
+
+        assert!(d.last().is_none());
+    }
+
+    #[test]
+    fn test_feed() {
+        let mut d = Deduper::new();
+
+        let emitted: Vec<_> = vec![1, 1, 2, 2, 2, 1, 3, 3]
+            .into_iter()
+            .filter_map(|v| d.feed(v))
+            .collect();
+
+        assert_eq!(emitted, vec![1, 2, 1, 3]);
+        assert_eq!(d.last(), Some(&3));
+    }
+}
@@ -0,0 +1,120 @@
+/// Tracked float smoothed with an exponential moving average.
+///
+/// Every sample moves the smoothed value, but it is only marked fresh once it
+/// has drifted more than `threshold` away from the value last marked fresh.
+#[derive(Copy, Clone)]
+pub struct TrackedEma {
+    fresh: bool,
+    val: f64,
+    reported: f64,
+    threshold: f64
+}
+
+impl TrackedEma {
+    pub fn new(val: f64, threshold: f64) -> Self {
+        TrackedEma {
+            fresh: true,
+            val,
+            reported: val,
+            threshold
+        }
+    }
+
+    /// Blend a new sample into the smoothed value as `alpha * sample + (1 - alpha) * old`,
+    /// marked as fresh if it moved beyond the threshold.
+    pub fn set_weighted(&mut self, sample: f64, alpha: f64) {
+        self.val = alpha * sample + (1.0 - alpha) * self.val;
+
+        if (self.val - self.reported).abs() > self.threshold {
+            self.reported = self.val;
+            self.fresh = true;
+        }
+    }
+
+    /// Get the current smoothed value, marking it as stale.
+    pub fn get(&mut self) -> f64 {
+        self.fresh = false;
+        self.val
+    }
+
+    /// Get the current smoothed value if it's been modified since last time we checked, marking
+    /// it as stale.
+    pub fn get_if_fresh(&mut self) -> Option<f64> {
+        if self.fresh {
+            Some(self.get())
+        } else {
+            None
+        }
+    }
+
+    // Get the current smoothed value without marking it.
+    pub fn peek(&self) -> f64 {
+        self.val
+    }
+
+    pub fn is_fresh(&self) -> bool {
+        self.fresh
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new() {
+        let t = TrackedEma::new(1.5, 0.1);
+
+        assert!(t.is_fresh());
+        assert_eq!(t.peek(), 1.5);
+    }
+
+    #[test]
+    fn test_step_input() {
+        let mut t = TrackedEma::new(0.0, 0.1);
+
+        // Reset to stale
+        t.get();
+
+        t.set_weighted(10.0, 0.5);
+
+        assert!(t.is_fresh());
+        assert_eq!(t.get(), 5.0);
+
+        t.set_weighted(10.0, 0.5);
+
+        assert!(t.is_fresh());
+        assert_eq!(t.get(), 7.5);
+
+        // Converges on the step
+        for _ in 0..20 {
+            t.set_weighted(10.0, 0.5);
+            t.get_if_fresh();
+        }
+
+        assert!((t.peek() - 10.0).abs() < 1e-3);
+
+        // Settled - further samples stay within the threshold
+        t.set_weighted(10.0, 0.5);
+
+        assert!(!t.is_fresh());
+    }
+
+    #[test]
+    fn test_small_moves_accumulate() {
+        let mut t = TrackedEma::new(0.0, 1.0);
+
+        // Reset to stale
+        t.get();
+
+        // Each step moves less than the threshold
+        t.set_weighted(3.0, 0.2);
+
+        assert!(!t.is_fresh());
+
+        // But the drift from the last fresh value adds up
+        t.set_weighted(3.0, 0.2);
+
+        assert!(t.is_fresh());
+    }
+}
@@ -0,0 +1,30 @@
+use std::error::Error;
+use std::fmt;
+
+/// Error returned when a value falls outside the allowed range.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct RangeError<T> {
+    pub value: T,
+    pub min: T,
+    pub max: T
+}
+
+impl<T: fmt::Display> fmt::Display for RangeError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "value {} is outside the range [{}, {}]", self.value, self.min, self.max)
+    }
+}
+
+impl<T: fmt::Debug + fmt::Display> Error for RangeError<T> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        let err = RangeError { value: 12, min: 0, max: 10 };
+
+        assert_eq!(err.to_string(), "value 12 is outside the range [0, 10]");
+    }
+}
@@ -0,0 +1,102 @@
+type OnUnconsumed<T> = Box<dyn FnOnce(&T)>;
+
+/// Tracked value that hands an unread value to a callback when dropped.
+///
+/// If the value is still fresh when the wrapper is dropped, the callback is
+/// called once with it, e.g. to log a warning or flush it elsewhere.
+pub struct TrackedFlush<T> {
+    fresh: bool,
+    val: T,
+    on_unconsumed: Option<OnUnconsumed<T>>
+}
+
+impl<T: PartialEq> TrackedFlush<T> {
+    pub fn new<F>(val: T, on_unconsumed: F) -> Self
+        where F: FnOnce(&T) + 'static
+    {
+        TrackedFlush {
+            fresh: true,
+            val,
+            on_unconsumed: Some(Box::new(on_unconsumed))
+        }
+    }
+
+    /// Set a new value, marked as fresh if not equal to the existing value.
+    pub fn set(&mut self, val: T) {
+        if self.val != val {
+            self.val = val;
+            self.fresh = true;
+        }
+    }
+
+    /// Get a reference to the current value, marking it as stale.
+    pub fn get(&mut self) -> &T {
+        self.fresh = false;
+        &self.val
+    }
+
+    /// Get the current value if it's been modified since last time we checked, marking it as stale.
+    pub fn get_if_fresh(&mut self) -> Option<&T> {
+        if self.fresh {
+            Some(self.get())
+        } else {
+            None
+        }
+    }
+
+    // Get the current value without marking it.
+    pub fn peek(&self) -> &T {
+        &self.val
+    }
+
+    pub fn is_fresh(&self) -> bool {
+        self.fresh
+    }
+}
+
+impl<T> Drop for TrackedFlush<T> {
+    fn drop(&mut self) {
+        if self.fresh {
+            if let Some(on_unconsumed) = self.on_unconsumed.take() {
+                on_unconsumed(&self.val);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_drop_unconsumed() {
+        let flushed = Rc::new(RefCell::new(Vec::new()));
+        let sink = flushed.clone();
+
+        {
+            let mut t = TrackedFlush::new(5, move |v: &i32| sink.borrow_mut().push(*v));
+
+            t.get();
+            t.set(6);
+        }
+
+        assert_eq!(*flushed.borrow(), vec![6]);
+    }
+
+    #[test]
+    fn test_drop_consumed() {
+        let flushed = Rc::new(RefCell::new(Vec::new()));
+        let sink = flushed.clone();
+
+        {
+            let mut t = TrackedFlush::new(5, move |v: &i32| sink.borrow_mut().push(*v));
+
+            t.set(6);
+            t.get();
+        }
+
+        assert!(flushed.borrow().is_empty());
+    }
+}
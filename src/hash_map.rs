@@ -0,0 +1,178 @@
+use reference::{TrackedRef, TrackedRefSet};
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+/// Map of values, each tracking its own freshness.
+pub struct TrackedMap<K, V> {
+    items: HashMap<K, TrackedRef<V>>
+}
+
+/// Values of a `TrackedMap` captured by `TrackedMap::snapshot`.
+#[derive(Clone)]
+pub struct MapSnapshot<K, V> {
+    items: HashMap<K, V>
+}
+
+/// Differences between a `TrackedMap` and an earlier snapshot, as returned by
+/// `TrackedMap::diff_since`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MapDiff<K: Eq + Hash, V> {
+    /// Keys present now but not in the snapshot.
+    pub added: HashSet<K>,
+    /// Keys whose value differs from the snapshot, with the old and new values.
+    pub changed: HashMap<K, (V, V)>,
+    /// Keys present in the snapshot but not now.
+    pub removed: HashSet<K>
+}
+
+impl<K: Eq + Hash, V: PartialEq> TrackedMap<K, V> {
+    pub fn new() -> Self {
+        TrackedMap {
+            items: HashMap::new()
+        }
+    }
+
+    /// Insert a value, marked as fresh if the key is new or the value differs from the existing
+    /// one.
+    pub fn insert(&mut self, key: K, val: V) {
+        match self.items.entry(key) {
+            Entry::Occupied(mut entry) => entry.get_mut().set(val),
+            Entry::Vacant(entry) => {
+                entry.insert(TrackedRef::new(val));
+            }
+        }
+    }
+
+    /// Remove a key, returning whether it was present.
+    pub fn remove(&mut self, key: &K) -> bool {
+        self.items.remove(key).is_some()
+    }
+
+    pub fn get(&self, key: &K) -> Option<&TrackedRef<V>> {
+        self.items.get(key)
+    }
+
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut TrackedRef<V>> {
+        self.items.get_mut(key)
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+}
+
+impl<K: Clone + Eq + Hash, V: Clone + PartialEq> TrackedMap<K, V> {
+    /// Capture every value, without marking them.
+    pub fn snapshot(&self) -> MapSnapshot<K, V> {
+        MapSnapshot {
+            items: self.items.iter().map(|(k, v)| (k.clone(), v.peek().clone())).collect()
+        }
+    }
+
+    /// Compare the current contents with `snap`, without marking anything.
+    pub fn diff_since(&self, snap: &MapSnapshot<K, V>) -> MapDiff<K, V> {
+        let mut diff = MapDiff {
+            added: HashSet::new(),
+            changed: HashMap::new(),
+            removed: HashSet::new()
+        };
+
+        for (key, tracked) in &self.items {
+            match snap.items.get(key) {
+                Some(old) if old != tracked.peek() => {
+                    diff.changed.insert(key.clone(), (old.clone(), tracked.peek().clone()));
+                }
+                Some(_) => {}
+                None => {
+                    diff.added.insert(key.clone());
+                }
+            }
+        }
+
+        for key in snap.items.keys() {
+            if !self.items.contains_key(key) {
+                diff.removed.insert(key.clone());
+            }
+        }
+
+        diff
+    }
+}
+
+impl<K: Eq + Hash, V: PartialEq> Default for TrackedMap<K, V> {
+    fn default() -> Self {
+        TrackedMap::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert() {
+        let mut m = TrackedMap::new();
+
+        m.insert("a", 1);
+
+        assert!(m.get(&"a").unwrap().is_fresh());
+
+        // Mark as stale
+        m.get_mut(&"a").unwrap().get();
+
+        // Same value - still stale
+        m.insert("a", 1);
+
+        assert!(!m.get(&"a").unwrap().is_fresh());
+
+        m.insert("a", 2);
+
+        assert!(m.get(&"a").unwrap().is_fresh());
+        assert_eq!(m.len(), 1);
+    }
+
+    #[test]
+    fn test_diff_since() {
+        let mut m = TrackedMap::new();
+
+        m.insert("kept", 1);
+        m.insert("changed", 2);
+        m.insert("removed", 3);
+
+        let snap = m.snapshot();
+
+        m.insert("changed", 20);
+        m.remove(&"removed");
+        m.insert("added", 4);
+
+        let diff = m.diff_since(&snap);
+
+        assert_eq!(diff.added, vec!["added"].into_iter().collect());
+        assert_eq!(diff.changed, vec![("changed", (2, 20))].into_iter().collect());
+        assert_eq!(diff.removed, vec!["removed"].into_iter().collect());
+    }
+
+    #[test]
+    fn test_diff_since_unchanged() {
+        let mut m = TrackedMap::new();
+
+        m.insert("a", 1);
+
+        let snap = m.snapshot();
+
+        // Changed and changed back
+        m.insert("a", 2);
+        m.insert("a", 1);
+
+        let diff = m.diff_since(&snap);
+
+        assert!(diff.added.is_empty());
+        assert!(diff.changed.is_empty());
+        assert!(diff.removed.is_empty());
+    }
+}
@@ -0,0 +1,103 @@
+/// Tracked float that records the magnitude of each change in a histogram.
+///
+/// Buckets are defined by ascending upper bounds: a change of absolute size
+/// `delta` is counted in the first bucket whose bound is `>= delta`, or in a
+/// final overflow bucket if it exceeds them all.
+pub struct TrackedHistogram {
+    fresh: bool,
+    val: f64,
+    bounds: Vec<f64>,
+    counts: Vec<u64>
+}
+
+impl TrackedHistogram {
+    /// Create a new wrapper around `val` with one bucket per bound, plus an overflow bucket.
+    pub fn new(val: f64, bounds: Vec<f64>) -> Self {
+        let counts = vec![0; bounds.len() + 1];
+
+        TrackedHistogram {
+            fresh: true,
+            val,
+            bounds,
+            counts
+        }
+    }
+
+    /// Set a new value, marked as fresh and recording the size of the change if not equal to the
+    /// existing value.
+    pub fn set(&mut self, val: f64) {
+        if self.val != val {
+            let delta = (val - self.val).abs();
+            let bucket = self.bounds.iter().position(|&b| delta <= b).unwrap_or(self.bounds.len());
+
+            self.counts[bucket] += 1;
+            self.val = val;
+            self.fresh = true;
+        }
+    }
+
+    /// Get the current value, marking it as stale.
+    pub fn get(&mut self) -> f64 {
+        self.fresh = false;
+        self.val
+    }
+
+    /// Get the current value if it's been modified since last time we checked, marking it as stale.
+    pub fn get_if_fresh(&mut self) -> Option<f64> {
+        if self.fresh {
+            Some(self.get())
+        } else {
+            None
+        }
+    }
+
+    // Get the current value without marking it.
+    pub fn peek(&self) -> f64 {
+        self.val
+    }
+
+    pub fn is_fresh(&self) -> bool {
+        self.fresh
+    }
+
+    /// Number of changes counted in each bucket, with the overflow bucket last.
+    pub fn histogram(&self) -> &[u64] {
+        &self.counts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new() {
+        let t = TrackedHistogram::new(0.0, vec![1.0, 10.0]);
+
+        assert!(t.is_fresh());
+        assert_eq!(t.histogram(), &[0, 0, 0]);
+    }
+
+    #[test]
+    fn test_histogram() {
+        let mut t = TrackedHistogram::new(0.0, vec![1.0, 10.0]);
+
+        // Deltas of 0.5, 1.0, 5.0, 10.0, 50.0 and 0.5
+        for &v in &[0.5, -0.5, 4.5, -5.5, 44.5, 45.0] {
+            t.set(v);
+        }
+
+        assert_eq!(t.histogram(), &[3, 2, 1]);
+    }
+
+    #[test]
+    fn test_same_val_not_counted() {
+        let mut t = TrackedHistogram::new(1.0, vec![1.0]);
+
+        t.get();
+        t.set(1.0);
+
+        assert!(!t.is_fresh());
+        assert_eq!(t.histogram(), &[0, 0]);
+    }
+}
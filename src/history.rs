@@ -0,0 +1,175 @@
+use std::collections::VecDeque;
+use std::mem;
+
+/// Tracked value that remembers a bounded number of previous values.
+///
+/// Every value-changing set pushes the old value into a ring buffer of
+/// `capacity` entries, dropping the oldest once full.
+pub struct TrackedHistory<T> {
+    fresh: bool,
+    val: T,
+    history: VecDeque<T>,
+    capacity: usize
+}
+
+impl<T: PartialEq> TrackedHistory<T> {
+    pub fn new(val: T, capacity: usize) -> Self {
+        TrackedHistory {
+            fresh: true,
+            val,
+            history: VecDeque::with_capacity(capacity),
+            capacity
+        }
+    }
+
+    /// Set a new value, marked as fresh and recording the old value if not equal to the existing
+    /// value.
+    pub fn set(&mut self, val: T) {
+        if self.val != val {
+            let old = mem::replace(&mut self.val, val);
+
+            if self.capacity > 0 {
+                if self.history.len() == self.capacity {
+                    self.history.pop_front();
+                }
+
+                self.history.push_back(old);
+            }
+
+            self.fresh = true;
+        }
+    }
+
+    /// Go back to the value that was current `n` changes ago, discarding the newer history.
+    ///
+    /// Marked as fresh if the restored value differs from the current one. Returns false, leaving
+    /// everything untouched, if fewer than `n` previous values are remembered.
+    pub fn rewind(&mut self, n: usize) -> bool {
+        if n > self.history.len() {
+            return false;
+        }
+
+        if n > 0 {
+            let keep = self.history.len() - n;
+
+            self.history.truncate(keep + 1);
+
+            let val = self.history.pop_back().unwrap();
+
+            if self.val != val {
+                self.val = val;
+                self.fresh = true;
+            }
+        }
+
+        true
+    }
+
+    /// Go back to the previous value. Equivalent to `rewind(1)`.
+    pub fn undo(&mut self) -> bool {
+        self.rewind(1)
+    }
+
+    /// Number of previous values remembered.
+    pub fn history_len(&self) -> usize {
+        self.history.len()
+    }
+
+    /// Get a reference to the current value, marking it as stale.
+    pub fn get(&mut self) -> &T {
+        self.fresh = false;
+        &self.val
+    }
+
+    /// Get the current value if it's been modified since last time we checked, marking it as stale.
+    pub fn get_if_fresh(&mut self) -> Option<&T> {
+        if self.fresh {
+            Some(self.get())
+        } else {
+            None
+        }
+    }
+
+    // Get the current value without marking it.
+    pub fn peek(&self) -> &T {
+        &self.val
+    }
+
+    pub fn is_fresh(&self) -> bool {
+        self.fresh
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sequence() -> TrackedHistory<i32> {
+        let mut t = TrackedHistory::new(1, 3);
+
+        t.set(2);
+        t.set(3);
+        t.set(4);
+
+        // Reset to stale
+        t.get();
+
+        t
+    }
+
+    #[test]
+    fn test_capacity() {
+        let mut t = sequence();
+
+        t.set(5);
+
+        // Oldest value dropped
+        assert_eq!(t.history_len(), 3);
+        assert!(!t.rewind(4));
+        assert!(t.rewind(3));
+        assert_eq!(t.peek(), &2);
+    }
+
+    #[test]
+    fn test_rewind_one() {
+        let mut t = sequence();
+
+        assert!(t.undo());
+        assert!(t.is_fresh());
+        assert_eq!(t.peek(), &3);
+        assert_eq!(t.history_len(), 2);
+    }
+
+    #[test]
+    fn test_rewind_two() {
+        let mut t = sequence();
+
+        assert!(t.rewind(2));
+        assert!(t.is_fresh());
+        assert_eq!(t.peek(), &2);
+        assert_eq!(t.history_len(), 1);
+
+        assert!(t.rewind(1));
+        assert_eq!(t.peek(), &1);
+    }
+
+    #[test]
+    fn test_rewind_too_far() {
+        let mut t = sequence();
+
+        assert!(!t.rewind(4));
+        assert!(!t.rewind(usize::MAX));
+        assert!(!t.is_fresh());
+        assert_eq!(t.peek(), &4);
+        assert_eq!(t.history_len(), 3);
+    }
+
+    #[test]
+    fn test_rewind_zero() {
+        let mut t = sequence();
+
+        assert!(t.rewind(0));
+        assert!(!t.is_fresh());
+        assert_eq!(t.peek(), &4);
+    }
+}
@@ -0,0 +1,136 @@
+use std::ops::{Add, Sub};
+
+/// Tracked value that is only marked fresh when it crosses a threshold.
+///
+/// Implements Schmitt-trigger semantics: the value has to rise above
+/// `threshold + band` to switch to the upper side, and fall below
+/// `threshold - band` to switch back, so oscillation within the band never
+/// marks it fresh.
+#[derive(Copy, Clone)]
+pub struct TrackedHysteresis<T> {
+    fresh: bool,
+    val: T,
+    threshold: T,
+    band: T,
+    above: bool
+}
+
+impl<T> TrackedHysteresis<T>
+    where T: PartialOrd + Copy + Add<Output = T> + Sub<Output = T>
+{
+    /// Create a new wrapper around `val`, starting on the upper side if `val >= threshold`.
+    pub fn new(val: T, threshold: T, band: T) -> Self {
+        TrackedHysteresis {
+            fresh: true,
+            val,
+            threshold,
+            band,
+            above: val >= threshold
+        }
+    }
+
+    /// Set a new value, marked as fresh if it crosses to the other side of the threshold.
+    pub fn set(&mut self, val: T) {
+        self.val = val;
+
+        if self.above && val < self.threshold - self.band {
+            self.above = false;
+            self.fresh = true;
+        } else if !self.above && val > self.threshold + self.band {
+            self.above = true;
+            self.fresh = true;
+        }
+    }
+
+    /// Get the current value, marking it as stale.
+    pub fn get(&mut self) -> T {
+        self.fresh = false;
+        self.val
+    }
+
+    /// Get the current value if it's crossed the threshold since last time we checked, marking it
+    /// as stale.
+    pub fn get_if_fresh(&mut self) -> Option<T> {
+        if self.fresh {
+            Some(self.get())
+        } else {
+            None
+        }
+    }
+
+    // Get the current value without marking it.
+    pub fn peek(&self) -> T {
+        self.val
+    }
+
+    pub fn is_fresh(&self) -> bool {
+        self.fresh
+    }
+
+    /// Whether the value is currently on the upper side of the threshold.
+    pub fn is_above(&self) -> bool {
+        self.above
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new() {
+        let t = TrackedHysteresis::new(5, 10, 2);
+
+        assert!(t.is_fresh());
+        assert!(!t.is_above());
+        assert_eq!(t.peek(), 5);
+    }
+
+    #[test]
+    fn test_oscillate_within_band() {
+        let mut t = TrackedHysteresis::new(5, 10, 2);
+
+        // Reset to stale
+        t.get();
+
+        for &v in &[9, 11, 12, 8, 10, 11] {
+            t.set(v);
+
+            assert!(!t.is_fresh());
+        }
+
+        assert!(!t.is_above());
+        assert_eq!(t.peek(), 11);
+    }
+
+    #[test]
+    fn test_crossing() {
+        let mut t = TrackedHysteresis::new(5.0, 10.0, 2.0);
+
+        // Reset to stale
+        t.get();
+
+        // Rise through the band
+        t.set(11.0);
+        t.set(12.5);
+
+        assert!(t.is_above());
+        assert_eq!(t.get_if_fresh(), Some(12.5));
+
+        // Dipping back into the band doesn't switch
+        t.set(9.0);
+
+        assert!(t.get_if_fresh().is_none());
+
+        // Fall through the band
+        t.set(7.5);
+
+        assert!(!t.is_above());
+        assert_eq!(t.get_if_fresh(), Some(7.5));
+
+        // Further falls stay on the same side
+        t.set(3.0);
+
+        assert!(t.get_if_fresh().is_none());
+    }
+}
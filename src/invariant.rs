@@ -0,0 +1,133 @@
+/// Tracked value with a contract checked on every set in debug builds.
+///
+/// With `debug_assertions` enabled, storing a value for which the invariant
+/// returns false panics. In release builds the invariant isn't stored or
+/// checked at all, and neither is the label given to `with_label`.
+pub struct TrackedInvariant<T> {
+    fresh: bool,
+    val: T,
+    #[cfg(debug_assertions)]
+    invariant: fn(&T) -> bool,
+    #[cfg(debug_assertions)]
+    label: Option<&'static str>
+}
+
+impl<T: PartialEq> TrackedInvariant<T> {
+    /// Create a new wrapper around `val`, which must itself satisfy `invariant`.
+    #[cfg_attr(not(debug_assertions), allow(unused_variables))]
+    pub fn new_with_invariant(val: T, invariant: fn(&T) -> bool) -> Self {
+        let t = TrackedInvariant {
+            fresh: true,
+            val,
+            #[cfg(debug_assertions)]
+            invariant,
+            #[cfg(debug_assertions)]
+            label: None
+        };
+
+        t.check(&t.val);
+        t
+    }
+
+    /// Attach a label, named in the panic message when the invariant is violated.
+    #[cfg_attr(not(debug_assertions), allow(unused_variables, unused_mut))]
+    pub fn with_label(mut self, label: &'static str) -> Self {
+        #[cfg(debug_assertions)]
+        {
+            self.label = Some(label);
+        }
+
+        self
+    }
+
+    #[cfg(debug_assertions)]
+    fn check(&self, val: &T) {
+        assert!((self.invariant)(val), "tracked value {}violates its invariant",
+                self.label.map_or(String::new(), |l| format!("{:?} ", l)));
+    }
+
+    #[cfg(not(debug_assertions))]
+    fn check(&self, _val: &T) {}
+
+    /// Set a new value, marked as fresh if not equal to the existing value.
+    ///
+    /// Panics in debug builds if the new value violates the invariant.
+    pub fn set(&mut self, val: T) {
+        if self.val != val {
+            self.check(&val);
+            self.val = val;
+            self.fresh = true;
+        }
+    }
+
+    /// Get a reference to the current value, marking it as stale.
+    pub fn get(&mut self) -> &T {
+        self.fresh = false;
+        &self.val
+    }
+
+    /// Get the current value if it's been modified since last time we checked, marking it as stale.
+    pub fn get_if_fresh(&mut self) -> Option<&T> {
+        if self.fresh {
+            Some(self.get())
+        } else {
+            None
+        }
+    }
+
+    // Get the current value without marking it.
+    pub fn peek(&self) -> &T {
+        &self.val
+    }
+
+    pub fn is_fresh(&self) -> bool {
+        self.fresh
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn non_negative(v: &i32) -> bool {
+        *v >= 0
+    }
+
+    #[test]
+    fn test_set_conforming() {
+        let mut t = TrackedInvariant::new_with_invariant(5, non_negative);
+
+        // Reset to stale
+        t.get();
+
+        t.set(0);
+
+        assert!(t.is_fresh());
+        assert_eq!(t.peek(), &0);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic]
+    fn test_set_violating() {
+        let mut t = TrackedInvariant::new_with_invariant(5, non_negative);
+
+        t.set(-1);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic]
+    fn test_new_violating() {
+        TrackedInvariant::new_with_invariant(-1, non_negative);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "tracked value \"count\" violates its invariant")]
+    fn test_set_violating_labeled() {
+        let mut t = TrackedInvariant::new_with_invariant(5, non_negative).with_label("count");
+
+        t.set(-1);
+    }
+}
@@ -0,0 +1,84 @@
+use value::Tracked;
+
+/// Read three values together if any of them is fresh, marking all of them as stale.
+///
+/// Returns `None` without touching anything if all three are stale.
+pub fn join3<A, B, C>(a: &mut Tracked<A>, b: &mut Tracked<B>, c: &mut Tracked<C>) -> Option<(A, B, C)>
+    where A: PartialEq + Copy,
+          B: PartialEq + Copy,
+          C: PartialEq + Copy
+{
+    if a.is_fresh() || b.is_fresh() || c.is_fresh() {
+        Some((a.get(), b.get(), c.get()))
+    } else {
+        None
+    }
+}
+
+/// Read four values together if any of them is fresh, marking all of them as stale.
+///
+/// Returns `None` without touching anything if all four are stale.
+pub fn join4<A, B, C, D>(
+    a: &mut Tracked<A>,
+    b: &mut Tracked<B>,
+    c: &mut Tracked<C>,
+    d: &mut Tracked<D>
+) -> Option<(A, B, C, D)>
+    where A: PartialEq + Copy,
+          B: PartialEq + Copy,
+          C: PartialEq + Copy,
+          D: PartialEq + Copy
+{
+    if a.is_fresh() || b.is_fresh() || c.is_fresh() || d.is_fresh() {
+        Some((a.get(), b.get(), c.get(), d.get()))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_join3() {
+        for mask in 0..8 {
+            let mut a = Tracked::new(1);
+            let mut b = Tracked::new('b');
+            let mut c = Tracked::new(3.0);
+
+            // Mark the inputs outside the mask as stale
+            if mask & 1 == 0 { a.get(); }
+            if mask & 2 == 0 { b.get(); }
+            if mask & 4 == 0 { c.get(); }
+
+            let joined = join3(&mut a, &mut b, &mut c);
+
+            if mask == 0 {
+                assert!(joined.is_none());
+            } else {
+                assert_eq!(joined, Some((1, 'b', 3.0)));
+            }
+
+            assert!(!a.is_fresh());
+            assert!(!b.is_fresh());
+            assert!(!c.is_fresh());
+        }
+    }
+
+    #[test]
+    fn test_join4() {
+        let mut a = Tracked::new(1);
+        let mut b = Tracked::new(2);
+        let mut c = Tracked::new(3);
+        let mut d = Tracked::new(4);
+
+        assert_eq!(join4(&mut a, &mut b, &mut c, &mut d), Some((1, 2, 3, 4)));
+        assert!(join4(&mut a, &mut b, &mut c, &mut d).is_none());
+
+        d.set(5);
+
+        assert_eq!(join4(&mut a, &mut b, &mut c, &mut d), Some((1, 2, 3, 5)));
+        assert!(!d.is_fresh());
+    }
+}
@@ -0,0 +1,88 @@
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+use value::Tracked;
+
+/// `Tracked` value with a label, used in `Debug` output and in panic messages.
+///
+/// Derefs to the inner `Tracked`, so it is used the same way.
+#[derive(Copy, Clone)]
+pub struct TrackedLabeled<T: PartialEq + Copy> {
+    tracked: Tracked<T>,
+    label: &'static str
+}
+
+impl<T: PartialEq + Copy> TrackedLabeled<T> {
+    pub fn new_labeled(val: T, label: &'static str) -> Self {
+        TrackedLabeled {
+            tracked: Tracked::new(val),
+            label
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        self.label
+    }
+
+    /// Panic if the value is still fresh, naming the label in the message.
+    ///
+    /// Only checked when `debug_assertions` are enabled.
+    pub fn debug_assert_stale(&self) {
+        debug_assert!(!self.tracked.is_fresh(), "tracked value {:?} is unexpectedly fresh", self.label);
+    }
+}
+
+impl<T: PartialEq + Copy> Deref for TrackedLabeled<T> {
+    type Target = Tracked<T>;
+
+    fn deref(&self) -> &Tracked<T> {
+        &self.tracked
+    }
+}
+
+impl<T: PartialEq + Copy> DerefMut for TrackedLabeled<T> {
+    fn deref_mut(&mut self) -> &mut Tracked<T> {
+        &mut self.tracked
+    }
+}
+
+impl<T: PartialEq + Copy + fmt::Debug> fmt::Debug for TrackedLabeled<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct(&format!("Tracked[{:?}]", self.label))
+            .field("val", &self.tracked.peek())
+            .field("fresh", &self.tracked.is_fresh())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new() {
+        let mut t = TrackedLabeled::new_labeled(0.5, "gain");
+
+        assert_eq!(t.label(), "gain");
+        assert_eq!(t.get_if_fresh(), Some(0.5));
+
+        t.set(0.75);
+
+        assert!(t.is_fresh());
+    }
+
+    #[test]
+    fn test_debug() {
+        let t = TrackedLabeled::new_labeled(0.5, "gain");
+
+        assert_eq!(format!("{:?}", t), "Tracked[\"gain\"] { val: 0.5, fresh: true }");
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "\"gain\"")]
+    fn test_debug_assert_stale() {
+        let t = TrackedLabeled::new_labeled(0.5, "gain");
+
+        t.debug_assert_stale();
+    }
+}
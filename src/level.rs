@@ -0,0 +1,119 @@
+/// Tracked value whose freshness follows a predicate on the value.
+///
+/// A value-changing set marks it fresh if the new value is active according to
+/// the predicate, and stale otherwise, so the signal clears itself when the
+/// value returns to a neutral state even if it was never read.
+pub struct TrackedLevel<T> {
+    fresh: bool,
+    val: T,
+    active: Box<dyn Fn(&T) -> bool>
+}
+
+impl<T: PartialEq> TrackedLevel<T> {
+    /// Create a new wrapper around `val`, fresh if `val` is active.
+    pub fn new<F>(val: T, active: F) -> Self
+        where F: Fn(&T) -> bool + 'static
+    {
+        TrackedLevel {
+            fresh: active(&val),
+            val,
+            active: Box::new(active)
+        }
+    }
+
+    /// Set a new value. If not equal to the existing value, it is marked as fresh when active
+    /// and as stale when not.
+    pub fn set(&mut self, val: T) {
+        if self.val != val {
+            self.fresh = (self.active)(&val);
+            self.val = val;
+        }
+    }
+
+    /// Get a reference to the current value, marking it as stale.
+    pub fn get(&mut self) -> &T {
+        self.fresh = false;
+        &self.val
+    }
+
+    /// Get the current value if it's become active since last time we checked, marking it as
+    /// stale.
+    pub fn get_if_fresh(&mut self) -> Option<&T> {
+        if self.fresh {
+            Some(self.get())
+        } else {
+            None
+        }
+    }
+
+    // Get the current value without marking it.
+    pub fn peek(&self) -> &T {
+        &self.val
+    }
+
+    pub fn is_fresh(&self) -> bool {
+        self.fresh
+    }
+
+    /// Whether the current value is active, regardless of freshness.
+    pub fn is_active(&self) -> bool {
+        (self.active)(&self.val)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new() {
+        let idle = TrackedLevel::new(0, |v: &i32| *v != 0);
+        let active = TrackedLevel::new(3, |v: &i32| *v != 0);
+
+        assert!(!idle.is_fresh());
+        assert!(active.is_fresh());
+    }
+
+    #[test]
+    fn test_enter_active() {
+        let mut t = TrackedLevel::new(0, |v: &i32| *v != 0);
+
+        t.set(2);
+
+        assert!(t.is_fresh());
+        assert!(t.is_active());
+        assert_eq!(t.get_if_fresh(), Some(&2));
+
+        // Another active value - fresh again
+        t.set(3);
+
+        assert!(t.is_fresh());
+    }
+
+    #[test]
+    fn test_auto_clear() {
+        let mut t = TrackedLevel::new(0, |v: &i32| *v != 0);
+
+        t.set(2);
+
+        // Back to neutral before being read - cleared
+        t.set(0);
+
+        assert!(!t.is_fresh());
+        assert!(!t.is_active());
+        assert!(t.get_if_fresh().is_none());
+    }
+
+    #[test]
+    fn test_set_same_val() {
+        let mut t = TrackedLevel::new(0, |v: &i32| *v != 0);
+
+        t.set(2);
+        t.get();
+
+        // Same active value - still stale
+        t.set(2);
+
+        assert!(!t.is_fresh());
+    }
+}
@@ -1,5 +1,74 @@
+#[cfg(feature = "futures")]
+extern crate futures;
+
+pub mod async_tracked;
+pub mod batch;
+pub mod broadcast;
+pub mod checkpoint;
+pub mod clock;
+pub mod coalesce;
+pub mod dedup;
+pub mod ema;
+pub mod error;
+pub mod flush;
+pub mod hash_map;
+pub mod histogram;
+pub mod history;
+pub mod hysteresis;
+pub mod invariant;
+pub mod join;
+pub mod labeled;
+pub mod level;
+pub mod map;
+pub mod meta;
+#[cfg(feature = "futures")]
+pub mod pipe;
+pub mod projection;
+pub mod rate;
 pub mod reference;
+pub mod relative;
+pub mod sampled;
+pub mod seq;
+pub mod settle;
+pub mod stamped;
+pub mod subscribe;
+pub mod throttle;
+pub mod tracked_value;
 pub mod value;
+pub mod vec;
 
-pub use self::value::Tracked;
-pub use self::reference::TrackedRef;
+pub use self::value::{Generation, HookAction, Signal, Tracked};
+pub use self::reference::{snapshot_value, snapshots_equal, TrackedOption, TrackedRef};
+pub use self::coalesce::TrackedCoalesce;
+pub use self::relative::TrackedRelative;
+pub use self::async_tracked::AsyncTracked;
+pub use self::ema::TrackedEma;
+pub use self::checkpoint::{CheckpointGroup, CheckpointHandle};
+pub use self::sampled::TrackedSampled;
+#[cfg(feature = "futures")]
+pub use self::pipe::TrackedPipe;
+pub use self::settle::TrackedSettle;
+pub use self::map::MapChain;
+pub use self::seq::TrackedSeq;
+pub use self::broadcast::Broadcast;
+pub use self::meta::TrackedMeta;
+pub use self::tracked_value::TrackedValue;
+pub use self::batch::{collect_and_consume, collect_freshness, count_fresh, BitSet};
+pub use self::dedup::Deduper;
+pub use self::vec::TrackedVec;
+pub use self::hysteresis::TrackedHysteresis;
+pub use self::join::{join3, join4};
+pub use self::clock::{Clock, MockClock, SystemClock};
+pub use self::throttle::TrackedThrottle;
+pub use self::histogram::TrackedHistogram;
+pub use self::history::TrackedHistory;
+pub use self::hash_map::TrackedMap;
+pub use self::level::TrackedLevel;
+pub use self::rate::TrackedRate;
+pub use self::error::RangeError;
+pub use self::subscribe::{SubscriptionState, TrackedNotify};
+pub use self::invariant::TrackedInvariant;
+pub use self::flush::TrackedFlush;
+pub use self::projection::FieldProjection;
+pub use self::stamped::{cmp_recency, TrackedStamped};
+pub use self::labeled::TrackedLabeled;
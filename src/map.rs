@@ -0,0 +1,109 @@
+use value::Tracked;
+
+/// Lazily evaluated chain of two projections over a tracked source.
+///
+/// The source value is run through `first` and then `second` only when the
+/// source is fresh, and the result is cached until it changes again.
+pub struct MapChain<A: PartialEq + Copy, B, C> {
+    source: Tracked<A>,
+    first: Box<dyn Fn(A) -> B>,
+    second: Box<dyn Fn(B) -> C>,
+    cached: Option<C>
+}
+
+impl<A: PartialEq + Copy, B, C> MapChain<A, B, C> {
+    pub fn new<F, G>(source: Tracked<A>, first: F, second: G) -> Self
+        where F: Fn(A) -> B + 'static,
+              G: Fn(B) -> C + 'static
+    {
+        MapChain {
+            source,
+            first: Box::new(first),
+            second: Box::new(second),
+            cached: None
+        }
+    }
+
+    /// Set a new source value, marked as fresh if not equal to the existing value.
+    pub fn set(&mut self, val: A) {
+        self.source.set(val);
+    }
+
+    /// Get the projected value, recomputing it only if the source has changed.
+    pub fn get(&mut self) -> &C {
+        let changed = match self.source.get_if_fresh() {
+            Some(a) => Some(a),
+            None if self.cached.is_none() => Some(self.source.get()),
+            None => None
+        };
+
+        if let Some(a) = changed {
+            self.cached = Some((self.second)((self.first)(a)));
+        }
+
+        self.cached.as_ref().unwrap()
+    }
+
+    pub fn source(&self) -> &Tracked<A> {
+        &self.source
+    }
+
+    /// Whether the next `get` will recompute the projection.
+    pub fn is_fresh(&self) -> bool {
+        self.source.is_fresh() || self.cached.is_none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_get() {
+        let mut m = MapChain::new(Tracked::new(3), |a: i32| a * 2, |b: i32| b.to_string());
+
+        assert!(m.is_fresh());
+        assert_eq!(m.get(), "6");
+        assert!(!m.is_fresh());
+    }
+
+    #[test]
+    fn test_recompute_on_change() {
+        let firsts = Rc::new(Cell::new(0));
+        let seconds = Rc::new(Cell::new(0));
+        let (f, g) = (firsts.clone(), seconds.clone());
+        let mut m = MapChain::new(
+            Tracked::new(3),
+            move |a: i32| { f.set(f.get() + 1); a + 1 },
+            move |b: i32| { g.set(g.get() + 1); b * 10 }
+        );
+
+        assert_eq!(m.get(), &40);
+        assert_eq!(m.get(), &40);
+
+        // Same value - no recompute
+        m.set(3);
+
+        assert_eq!(m.get(), &40);
+        assert_eq!((firsts.get(), seconds.get()), (1, 1));
+
+        m.set(4);
+
+        assert_eq!(m.get(), &50);
+        assert_eq!((firsts.get(), seconds.get()), (2, 2));
+    }
+
+    #[test]
+    fn test_stale_source() {
+        let mut source = Tracked::new(1);
+
+        // Source already consumed elsewhere - still computed on first get
+        source.get();
+
+        let mut m = MapChain::new(source, |a: i32| a + 1, |b: i32| b + 1);
+
+        assert_eq!(m.get(), &3);
+    }
+}
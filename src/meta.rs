@@ -0,0 +1,99 @@
+/// Tracked value carrying metadata that doesn't take part in change detection.
+///
+/// The metadata (e.g. a timestamp or source id) is replaced whenever a set
+/// changes the value, and ignored otherwise.
+pub struct TrackedMeta<T, M> {
+    fresh: bool,
+    val: T,
+    meta: M
+}
+
+impl<T: PartialEq, M> TrackedMeta<T, M> {
+    pub fn new(val: T, meta: M) -> Self {
+        TrackedMeta {
+            fresh: true,
+            val,
+            meta
+        }
+    }
+
+    /// Set a new value, marked as fresh and storing `meta` if not equal to the existing value.
+    pub fn set(&mut self, val: T, meta: M) {
+        if self.val != val {
+            self.val = val;
+            self.meta = meta;
+            self.fresh = true;
+        }
+    }
+
+    /// Get a reference to the current value, marking it as stale.
+    pub fn get(&mut self) -> &T {
+        self.fresh = false;
+        &self.val
+    }
+
+    /// Get the current value if it's been modified since last time we checked, marking it as stale.
+    pub fn get_if_fresh(&mut self) -> Option<&T> {
+        if self.fresh {
+            Some(self.get())
+        } else {
+            None
+        }
+    }
+
+    // Get the current value without marking it.
+    pub fn peek(&self) -> &T {
+        &self.val
+    }
+
+    /// Metadata stored with the last value-changing set.
+    pub fn meta(&self) -> &M {
+        &self.meta
+    }
+
+    pub fn is_fresh(&self) -> bool {
+        self.fresh
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new() {
+        let t = TrackedMeta::new(5, "init");
+
+        assert!(t.is_fresh());
+        assert_eq!(t.peek(), &5);
+        assert_eq!(t.meta(), &"init");
+    }
+
+    #[test]
+    fn test_set_same_val() {
+        let mut t = TrackedMeta::new(5, "init");
+
+        // Reset to stale
+        t.get();
+
+        // Same value - metadata is not replaced
+        t.set(5, "sensor");
+
+        assert!(!t.is_fresh());
+        assert_eq!(t.meta(), &"init");
+    }
+
+    #[test]
+    fn test_set_different_val() {
+        let mut t = TrackedMeta::new(5, "init");
+
+        // Reset to stale
+        t.get();
+
+        t.set(6, "sensor");
+
+        assert!(t.is_fresh());
+        assert_eq!(t.peek(), &6);
+        assert_eq!(t.meta(), &"sensor");
+    }
+}
@@ -0,0 +1,238 @@
+use futures::{Sink, Stream};
+use std::convert::Infallible;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+
+struct Shared<T> {
+    fresh: bool,
+    val: T,
+    closed: bool,
+    dropped: u64,
+    wakers: Vec<Waker>
+}
+
+impl<T> Shared<T> {
+    fn wake_all(&mut self) {
+        for waker in self.wakers.drain(..) {
+            waker.wake();
+        }
+    }
+}
+
+/// Tracked value usable as both a `Sink` and a `Stream`.
+///
+/// Items sent into the sink are applied with `set`, and the stream yields the
+/// value whenever it is fresh. Backpressure is latest-wins: the sink is always
+/// ready and never buffers, so values sent faster than the stream is polled
+/// are coalesced and only the most recent one is yielded.
+///
+/// Clones share the same value and freshness, so when several clones are
+/// polled as streams each fresh value goes to whichever polls first; every
+/// waiting clone is woken on a change. Closing any clone ends the stream once
+/// the last fresh value has been yielded.
+pub struct TrackedPipe<T> {
+    shared: Arc<Mutex<Shared<T>>>
+}
+
+impl<T: PartialEq> TrackedPipe<T> {
+    pub fn new(val: T) -> Self {
+        TrackedPipe {
+            shared: Arc::new(Mutex::new(Shared {
+                fresh: true,
+                val,
+                closed: false,
+                dropped: 0,
+                wakers: Vec::new()
+            }))
+        }
+    }
+
+    /// Set a new value, marked as fresh if not equal to the existing value.
+    pub fn set(&self, val: T) {
+        let mut shared = self.shared.lock().unwrap();
+
+        if shared.val != val {
+            if shared.fresh {
+                shared.dropped = shared.dropped.wrapping_add(1);
+            }
+
+            shared.val = val;
+            shared.fresh = true;
+            shared.wake_all();
+        }
+    }
+
+    pub fn is_fresh(&self) -> bool {
+        self.shared.lock().unwrap().fresh
+    }
+
+    /// Number of value-changing sets that overwrote a fresh value before the stream yielded it.
+    pub fn dropped_changes(&self) -> u64 {
+        self.shared.lock().unwrap().dropped
+    }
+}
+
+impl<T: Clone> TrackedPipe<T> {
+    // Get the current value without marking it.
+    pub fn peek(&self) -> T {
+        self.shared.lock().unwrap().val.clone()
+    }
+}
+
+impl<T> Clone for TrackedPipe<T> {
+    fn clone(&self) -> Self {
+        TrackedPipe {
+            shared: self.shared.clone()
+        }
+    }
+}
+
+impl<T: PartialEq> Sink<T> for TrackedPipe<T> {
+    type Error = Infallible;
+
+    fn poll_ready(self: Pin<&mut Self>, _cx: &mut Context) -> Poll<Result<(), Infallible>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: T) -> Result<(), Infallible> {
+        TrackedPipe::set(&self, item);
+        Ok(())
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context) -> Poll<Result<(), Infallible>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context) -> Poll<Result<(), Infallible>> {
+        let mut shared = self.shared.lock().unwrap();
+
+        shared.closed = true;
+        shared.wake_all();
+
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<T: Clone> Stream for TrackedPipe<T> {
+    type Item = T;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<T>> {
+        let mut shared = self.shared.lock().unwrap();
+
+        if shared.fresh {
+            shared.fresh = false;
+            Poll::Ready(Some(shared.val.clone()))
+        } else if shared.closed {
+            Poll::Ready(None)
+        } else {
+            if !shared.wakers.iter().any(|w| w.will_wake(cx.waker())) {
+                shared.wakers.push(cx.waker().clone());
+            }
+
+            Poll::Pending
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::executor::block_on;
+    use futures::future::join;
+    use futures::{stream, FutureExt, SinkExt, StreamExt};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::task::Wake;
+
+    struct CountingWaker(AtomicUsize);
+
+    impl Wake for CountingWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn test_new() {
+        let pipe = TrackedPipe::new(5);
+
+        assert!(pipe.is_fresh());
+        assert_eq!(pipe.peek(), 5);
+    }
+
+    #[test]
+    fn test_dedup() {
+        let mut sink = TrackedPipe::new(0);
+        let mut out = sink.clone();
+        let mut changes = Vec::new();
+
+        // Consume the initial value
+        assert_eq!(block_on(out.next()), Some(0));
+
+        for v in [1, 1, 2, 2, 2, 3, 3] {
+            block_on(sink.send(v)).unwrap();
+
+            if let Some(Some(change)) = out.next().now_or_never() {
+                changes.push(change);
+            }
+        }
+
+        assert_eq!(changes, vec![1, 2, 3]);
+
+        block_on(sink.close()).unwrap();
+
+        assert_eq!(block_on(out.next()), None);
+    }
+
+    #[test]
+    fn test_forward_latest_wins() {
+        let pipe = TrackedPipe::new(0);
+        let source = stream::iter(vec![1, 1, 2, 2, 3]).map(Ok);
+
+        // The source is drained before the output is polled, so only the latest value survives
+        let (res, out) = block_on(join(
+            source.forward(pipe.clone()),
+            pipe.clone().collect::<Vec<_>>()
+        ));
+
+        assert!(res.is_ok());
+        assert_eq!(out, vec![3]);
+
+        // 0 was never yielded, and 1 and 2 were overwritten
+        assert_eq!(pipe.dropped_changes(), 3);
+    }
+
+    #[test]
+    fn test_clones_all_woken() {
+        let pipe = TrackedPipe::new(0);
+        let mut a = pipe.clone();
+        let mut b = pipe.clone();
+
+        let wake_a = Arc::new(CountingWaker(AtomicUsize::new(0)));
+        let wake_b = Arc::new(CountingWaker(AtomicUsize::new(0)));
+        let waker_a = Waker::from(wake_a.clone());
+        let waker_b = Waker::from(wake_b.clone());
+
+        // Consume the initial value
+        assert_eq!(block_on(a.next()), Some(0));
+
+        assert!(Pin::new(&mut a).poll_next(&mut Context::from_waker(&waker_a)).is_pending());
+        assert!(Pin::new(&mut b).poll_next(&mut Context::from_waker(&waker_b)).is_pending());
+
+        pipe.set(1);
+
+        assert_eq!(wake_a.0.load(Ordering::SeqCst), 1);
+        assert_eq!(wake_b.0.load(Ordering::SeqCst), 1);
+
+        // Closing wakes every waiting clone too
+        assert_eq!(block_on(a.next()), Some(1));
+        assert!(Pin::new(&mut a).poll_next(&mut Context::from_waker(&waker_a)).is_pending());
+        assert!(Pin::new(&mut b).poll_next(&mut Context::from_waker(&waker_b)).is_pending());
+
+        block_on(pipe.clone().close()).unwrap();
+
+        assert_eq!(wake_a.0.load(Ordering::SeqCst), 2);
+        assert_eq!(wake_b.0.load(Ordering::SeqCst), 2);
+        assert_eq!(block_on(b.next()), None);
+    }
+}
@@ -0,0 +1,92 @@
+use reference::TrackedRef;
+use std::marker::PhantomData;
+
+/// Tracks changes to one field of a `TrackedRef`, independently of the rest.
+///
+/// Created by `TrackedRef::project`. The projection doesn't borrow the source,
+/// so the source can be modified freely in between checks; instead it is passed
+/// in to each check. It remembers the last field value seen and is fresh
+/// whenever the field differs from it, regardless of the source's own
+/// freshness.
+pub struct FieldProjection<T, U, F> {
+    last: U,
+    project: F,
+    source: PhantomData<fn(&T)>
+}
+
+impl<T, U: Clone + PartialEq, F: Fn(&T) -> &U> FieldProjection<T, U, F> {
+    pub(crate) fn new(source: &TrackedRef<T>, project: F) -> Self {
+        FieldProjection {
+            last: project(source.peek()).clone(),
+            project,
+            source: PhantomData
+        }
+    }
+
+    /// Whether the field has changed since it was last seen.
+    pub fn is_fresh(&self, source: &TrackedRef<T>) -> bool {
+        *(self.project)(source.peek()) != self.last
+    }
+
+    /// Get the field if it has changed since it was last seen, marking it as seen.
+    ///
+    /// The source's own freshness is not affected.
+    pub fn get_if_fresh<'a>(&mut self, source: &'a TrackedRef<T>) -> Option<&'a U> {
+        let field = (self.project)(source.peek());
+
+        if *field != self.last {
+            self.last = field.clone();
+            Some(field)
+        } else {
+            None
+        }
+    }
+
+    /// The field value last seen.
+    pub fn last(&self) -> &U {
+        &self.last
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use reference::{TrackedRef, TrackedRefSet};
+
+    #[derive(Clone, PartialEq)]
+    struct Settings {
+        name: &'static str,
+        volume: u8
+    }
+
+    #[test]
+    fn test_other_field_changes() {
+        let mut t = TrackedRef::new(Settings { name: "a", volume: 5 });
+        let mut volume = t.project(|s: &Settings| &s.volume);
+
+        t.set(Settings { name: "b", volume: 5 });
+
+        // Whole struct is fresh, projected field isn't
+        assert!(t.is_fresh());
+        assert!(!volume.is_fresh(&t));
+        assert!(volume.get_if_fresh(&t).is_none());
+    }
+
+    #[test]
+    fn test_projected_field_changes() {
+        let mut t = TrackedRef::new(Settings { name: "a", volume: 5 });
+        let mut volume = t.project(|s: &Settings| &s.volume);
+
+        // Mark the source as stale
+        t.get();
+
+        t.get_mut().volume = 7;
+
+        assert!(volume.is_fresh(&t));
+        assert_eq!(volume.get_if_fresh(&t), Some(&7));
+        assert!(!volume.is_fresh(&t));
+        assert_eq!(volume.last(), &7);
+
+        // Source freshness is independent
+        assert!(t.is_fresh());
+    }
+}
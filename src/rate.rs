@@ -0,0 +1,154 @@
+use clock::{Clock, SystemClock};
+use std::time::Instant;
+
+/// Tracked rate of change of a sampled float.
+///
+/// Each sample updates the rate as `(val - prev_val) / (now - prev_time)`, in
+/// units per second, and the rate is marked fresh when it differs from the
+/// previous rate by more than `threshold`.
+pub struct TrackedRate<C = SystemClock> {
+    fresh: bool,
+    val: f64,
+    time: Instant,
+    rate: f64,
+    threshold: f64,
+    clock: C
+}
+
+impl TrackedRate {
+    pub fn new(val: f64, threshold: f64) -> Self {
+        TrackedRate::new_with_clock(val, threshold, SystemClock)
+    }
+}
+
+impl<C: Clock> TrackedRate<C> {
+    /// Create a new wrapper with an initial sample taken now, and a rate of zero.
+    pub fn new_with_clock(val: f64, threshold: f64, clock: C) -> Self {
+        TrackedRate {
+            fresh: true,
+            val,
+            time: clock.now(),
+            rate: 0.0,
+            threshold,
+            clock
+        }
+    }
+
+    /// Record a sample taken now according to the clock.
+    pub fn set(&mut self, val: f64) {
+        let now = self.clock.now();
+        self.set_at(val, now);
+    }
+
+    /// Record a sample taken at `now`, marking the rate as fresh if it changed by more than the
+    /// threshold.
+    ///
+    /// Samples that aren't later than the previous one are ignored.
+    pub fn set_at(&mut self, val: f64, now: Instant) {
+        if now <= self.time {
+            return;
+        }
+
+        let rate = (val - self.val) / now.duration_since(self.time).as_secs_f64();
+
+        if (rate - self.rate).abs() > self.threshold {
+            self.fresh = true;
+        }
+
+        self.val = val;
+        self.time = now;
+        self.rate = rate;
+    }
+
+    /// Get the current rate, marking it as stale.
+    pub fn get(&mut self) -> f64 {
+        self.fresh = false;
+        self.rate
+    }
+
+    /// Get the current rate if it's changed since last time we checked, marking it as stale.
+    pub fn get_if_fresh(&mut self) -> Option<f64> {
+        if self.fresh {
+            Some(self.get())
+        } else {
+            None
+        }
+    }
+
+    // Get the current rate without marking it.
+    pub fn rate(&self) -> f64 {
+        self.rate
+    }
+
+    /// Latest sampled value.
+    pub fn value(&self) -> f64 {
+        self.val
+    }
+
+    pub fn is_fresh(&self) -> bool {
+        self.fresh
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clock::MockClock;
+    use std::time::Duration;
+
+    #[test]
+    fn test_new() {
+        let t = TrackedRate::new(5.0, 0.1);
+
+        assert!(t.is_fresh());
+        assert_eq!(t.rate(), 0.0);
+        assert_eq!(t.value(), 5.0);
+    }
+
+    #[test]
+    fn test_set_at() {
+        let clock = MockClock::new();
+        let start = clock.now();
+        let mut t = TrackedRate::new_with_clock(0.0, 0.5, clock);
+
+        // Reset to stale
+        t.get();
+
+        // Constant velocity of 2/s
+        t.set_at(2.0, start + Duration::from_secs(1));
+
+        assert_eq!(t.get_if_fresh(), Some(2.0));
+
+        t.set_at(4.0, start + Duration::from_secs(2));
+        t.set_at(6.25, start + Duration::from_secs(3));
+
+        // Rate barely moved - still stale
+        assert!(!t.is_fresh());
+        assert!((t.rate() - 2.25).abs() < 1e-9);
+
+        // Accelerates
+        t.set_at(10.25, start + Duration::from_secs(4));
+
+        assert_eq!(t.get_if_fresh(), Some(4.0));
+    }
+
+    #[test]
+    fn test_set_with_clock() {
+        let clock = MockClock::new();
+        let mut t = TrackedRate::new_with_clock(0.0, 0.5, clock.clone());
+
+        // Reset to stale
+        t.get();
+
+        clock.advance(Duration::from_millis(500));
+        t.set(3.0);
+
+        assert_eq!(t.get_if_fresh(), Some(6.0));
+
+        // No time passed - ignored
+        t.set(100.0);
+
+        assert_eq!(t.value(), 3.0);
+        assert!(!t.is_fresh());
+    }
+}
@@ -1,3 +1,8 @@
+use projection::FieldProjection;
+use std::ops::{Deref, DerefMut};
+use std::sync::Arc;
+use tracked_value::TrackedValue;
+
 /// Tracked value wrapper for types to be modified by reference.
 pub struct TrackedRef<T> {
     fresh: bool,
@@ -41,6 +46,93 @@ impl<T> TrackedRef<T> {
     pub fn is_fresh(&self) -> bool {
         self.fresh
     }
+
+    /// Get a borrowed view of the value with the same freshness, without marking it.
+    pub fn as_ref(&self) -> TrackedRef<&T> {
+        TrackedRef {
+            fresh: self.fresh,
+            val: &self.val
+        }
+    }
+
+    /// Track changes to a single field of the value, starting from its current state.
+    ///
+    /// See `FieldProjection` for how the field's freshness is tracked.
+    pub fn project<U, F>(&self, f: F) -> FieldProjection<T, U, F>
+        where U: Clone + PartialEq,
+              F: Fn(&T) -> &U
+    {
+        FieldProjection::new(self, f)
+    }
+
+    /// Restore the value and freshness captured by `snapshot`.
+    pub fn restore(&mut self, snap: Snapshot<T>) {
+        self.fresh = snap.fresh;
+        self.val = snap.val;
+    }
+
+    /// View as a `TrackedValue` trait object.
+    pub fn as_dyn(&mut self) -> &mut dyn TrackedValue {
+        self
+    }
+
+    /// View as a read-only `TrackedValue` trait object.
+    pub fn as_dyn_ref(&self) -> &dyn TrackedValue {
+        self
+    }
+
+    /// Panic if the value is still fresh, i.e. it should have been read by now.
+    ///
+    /// Only checked when `debug_assertions` are enabled.
+    pub fn debug_assert_stale(&self) {
+        debug_assert!(!self.fresh, "tracked value is unexpectedly fresh");
+    }
+}
+
+impl<T: Clone> TrackedRef<T> {
+    /// Capture the current value and freshness, without marking it.
+    pub fn snapshot(&self) -> Snapshot<T> {
+        Snapshot {
+            fresh: self.fresh,
+            val: self.val.clone()
+        }
+    }
+}
+
+impl<T: ?Sized + ToOwned> TrackedRef<&T> {
+    /// Clone a borrowed view into an owned wrapper with the same freshness.
+    pub fn to_owned_tracked(&self) -> TrackedRef<T::Owned> {
+        TrackedRef {
+            fresh: self.fresh,
+            val: self.val.to_owned()
+        }
+    }
+}
+
+/// Value and freshness of a `TrackedRef` captured by `TrackedRef::snapshot`.
+#[derive(Clone)]
+pub struct Snapshot<T> {
+    fresh: bool,
+    val: T
+}
+
+/// Whether two snapshots captured the same value and the same freshness.
+pub fn snapshots_equal<T: PartialEq>(a: &Snapshot<T>, b: &Snapshot<T>) -> bool {
+    a.fresh == b.fresh && a.val == b.val
+}
+
+/// Get a copy of the value captured by a snapshot.
+pub fn snapshot_value<T: Clone>(snap: &Snapshot<T>) -> T {
+    snap.val.clone()
+}
+
+impl<T> From<Snapshot<T>> for TrackedRef<T> {
+    fn from(snap: Snapshot<T>) -> Self {
+        TrackedRef {
+            fresh: snap.fresh,
+            val: snap.val
+        }
+    }
 }
 
 pub trait TrackedRefSet<T: PartialEq> {
@@ -59,6 +151,139 @@ impl<T: PartialEq> TrackedRefSet<T> for TrackedRef<T> {
     }
 }
 
+/// Tracked optional value.
+pub type TrackedOption<T> = TrackedRef<Option<T>>;
+
+impl<T> TrackedRef<Option<T>> {
+    // Get the inner value, or `default` if there is none, without marking it.
+    pub fn peek_or<'a>(&'a self, default: &'a T) -> &'a T {
+        self.val.as_ref().unwrap_or(default)
+    }
+
+    /// Set the value if there is none yet, marking it as fresh.
+    ///
+    /// Returns whether the value was set; an existing value is left untouched.
+    pub fn set_if_absent(&mut self, val: T) -> bool {
+        if self.val.is_some() {
+            return false;
+        }
+
+        self.val = Some(val);
+        self.fresh = true;
+        true
+    }
+}
+
+impl<T, E> TrackedRef<Result<T, E>> {
+    /// Get the `Ok` value if it's fresh, marking it as stale.
+    ///
+    /// A fresh `Err` is left fresh for `get_err_if_fresh`.
+    pub fn get_ok_if_fresh(&mut self) -> Option<&T> {
+        if self.fresh && self.val.is_ok() {
+            self.get().as_ref().ok()
+        } else {
+            None
+        }
+    }
+
+    /// Get the `Err` value if it's fresh, marking it as stale.
+    ///
+    /// A fresh `Ok` is left fresh for `get_ok_if_fresh`.
+    pub fn get_err_if_fresh(&mut self) -> Option<&E> {
+        if self.fresh && self.val.is_err() {
+            self.get().as_ref().err()
+        } else {
+            None
+        }
+    }
+}
+
+impl TrackedRef<Vec<f32>> {
+    /// Set a new vector, marked as fresh if the lengths differ or any element differs from the
+    /// existing one by more than `epsilon`.
+    ///
+    /// Smaller differences are discarded, leaving the existing vector in place. A NaN on either
+    /// side always counts as a change. Returns whether the vector was replaced.
+    pub fn set_approx(&mut self, val: Vec<f32>, epsilon: f32) -> bool {
+        let changed = self.val.len() != val.len()
+            || self.val.iter().zip(&val).any(|(a, b)| {
+                let diff = (a - b).abs();
+                a != b && (diff.is_nan() || diff > epsilon)
+            });
+
+        if changed {
+            self.set(val);
+        }
+
+        changed
+    }
+}
+
+impl<T: Clone + PartialEq> TrackedRef<Arc<T>> {
+    /// Get a copy-on-write mutable reference to the shared value.
+    ///
+    /// Reading through the guard never clones. On the first write the value is cloned into a
+    /// new allocation if the `Arc` has other owners, as with `Arc::make_mut`; if it is uniquely
+    /// owned it is modified in place, keeping a copy to compare against. Unlike `get_mut`, it is
+    /// only marked as fresh if the value actually changed, which is checked when the returned
+    /// guard is dropped.
+    pub fn make_mut(&mut self) -> MakeMut<'_, T> {
+        MakeMut {
+            before: None,
+            tracked: self
+        }
+    }
+}
+
+/// Value before the first write through a `MakeMut` guard.
+enum Before<T> {
+    // The old allocation, still held by other owners
+    Shared(Arc<T>),
+    // A copy of the value, which is being modified in place
+    Unique(T)
+}
+
+/// Guard returned by `TrackedRef::make_mut`, marking the value as fresh on drop if it changed.
+pub struct MakeMut<'a, T: 'a + Clone + PartialEq> {
+    before: Option<Before<T>>,
+    tracked: &'a mut TrackedRef<Arc<T>>
+}
+
+impl<'a, T: Clone + PartialEq> Deref for MakeMut<'a, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.tracked.val
+    }
+}
+
+impl<'a, T: Clone + PartialEq> DerefMut for MakeMut<'a, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        if self.before.is_none() {
+            self.before = Some(match Arc::get_mut(&mut self.tracked.val) {
+                Some(val) => Before::Unique(val.clone()),
+                None => Before::Shared(self.tracked.val.clone())
+            });
+        }
+
+        Arc::make_mut(&mut self.tracked.val)
+    }
+}
+
+impl<'a, T: Clone + PartialEq> Drop for MakeMut<'a, T> {
+    fn drop(&mut self) {
+        let changed = match self.before {
+            Some(Before::Shared(ref old)) => **old != *self.tracked.val,
+            Some(Before::Unique(ref old)) => *old != *self.tracked.val,
+            None => false
+        };
+
+        if changed {
+            self.tracked.fresh = true;
+        }
+    }
+}
+
 impl<T: Clone + PartialEq> Clone for TrackedRef<T> {
     fn clone(&self) -> Self {
         TrackedRef {
@@ -143,6 +368,177 @@ mod tests {
         assert_eq!(t.peek(), &Wrapper(888));
     }
 
+    #[test]
+    fn test_set_approx_within_epsilon() {
+        let mut t = TrackedRef::new(vec![1.0, 2.0, 3.0]);
+
+        // Mark as stale
+        t.get();
+
+        assert!(!t.set_approx(vec![1.0005, 1.9995, 3.0], 0.001));
+        assert!(!t.is_fresh());
+        assert_eq!(t.peek(), &vec![1.0, 2.0, 3.0]);
+    }
+
+    #[test]
+    fn test_set_approx_beyond_epsilon() {
+        let mut t = TrackedRef::new(vec![1.0, 2.0, 3.0]);
+
+        // Mark as stale
+        t.get();
+
+        assert!(t.set_approx(vec![1.0, 2.01, 3.0], 0.001));
+        assert!(t.is_fresh());
+        assert_eq!(t.peek(), &vec![1.0, 2.01, 3.0]);
+    }
+
+    #[test]
+    fn test_set_approx_nan() {
+        let mut t = TrackedRef::new(vec![1.0]);
+
+        // Reset to stale
+        t.get();
+
+        assert!(t.set_approx(vec![f32::NAN], 0.01));
+        assert!(t.is_fresh());
+        assert!(t.peek()[0].is_nan());
+    }
+
+    #[test]
+    fn test_set_approx_infinite() {
+        let mut t = TrackedRef::new(vec![f32::INFINITY, 1.0]);
+
+        // Reset to stale
+        t.get();
+
+        assert!(!t.set_approx(vec![f32::INFINITY, 1.0], 0.01));
+        assert!(!t.is_fresh());
+
+        assert!(t.set_approx(vec![f32::NEG_INFINITY, 1.0], 0.01));
+        assert!(t.is_fresh());
+    }
+
+    #[test]
+    fn test_set_approx_length() {
+        let mut t = TrackedRef::new(vec![1.0, 2.0]);
+
+        // Mark as stale
+        t.get();
+
+        assert!(t.set_approx(vec![1.0, 2.0, 0.0], 0.001));
+        assert!(t.is_fresh());
+    }
+
+    #[test]
+    fn test_make_mut_shared() {
+        let shared = Arc::new(vec![1, 2]);
+        let mut t = TrackedRef::new(shared.clone());
+
+        // Mark as stale
+        t.get();
+
+        t.make_mut().push(3);
+
+        // Cloned on write - the other owner is untouched
+        assert!(t.is_fresh());
+        assert_eq!(**t.peek(), vec![1, 2, 3]);
+        assert_eq!(*shared, vec![1, 2]);
+        assert!(!Arc::ptr_eq(t.peek(), &shared));
+    }
+
+    #[test]
+    fn test_make_mut_clones() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        struct Counted {
+            val: i32,
+            clones: Arc<AtomicUsize>
+        }
+
+        impl Clone for Counted {
+            fn clone(&self) -> Self {
+                self.clones.fetch_add(1, Ordering::SeqCst);
+
+                Counted {
+                    val: self.val,
+                    clones: self.clones.clone()
+                }
+            }
+        }
+
+        impl PartialEq for Counted {
+            fn eq(&self, other: &Self) -> bool {
+                self.val == other.val
+            }
+        }
+
+        let clones = Arc::new(AtomicUsize::new(0));
+        let mut t = TrackedRef::new(Arc::new(Counted { val: 1, clones: clones.clone() }));
+
+        // Mark as stale
+        t.get();
+
+        // Read only - no clone
+        assert_eq!(t.make_mut().val, 1);
+        assert_eq!(clones.load(Ordering::SeqCst), 0);
+        assert!(!t.is_fresh());
+
+        // Written in place - one copy kept to compare against, however many writes
+        {
+            let mut v = t.make_mut();
+            v.val = 2;
+            v.val = 3;
+        }
+
+        assert_eq!(clones.load(Ordering::SeqCst), 1);
+        assert!(t.is_fresh());
+        assert_eq!(t.peek().val, 3);
+    }
+
+    #[test]
+    fn test_make_mut_unique() {
+        let mut t = TrackedRef::new(Arc::new(vec![1, 2]));
+        let ptr = Arc::as_ptr(t.peek());
+
+        // Mark as stale
+        t.get();
+
+        t.make_mut().push(3);
+
+        // Not shared - modified in place
+        assert!(t.is_fresh());
+        assert_eq!(**t.peek(), vec![1, 2, 3]);
+        assert_eq!(Arc::as_ptr(t.peek()), ptr);
+    }
+
+    #[test]
+    fn test_make_mut_unchanged() {
+        let shared = Arc::new(vec![1, 2]);
+        let mut t = TrackedRef::new(shared.clone());
+
+        // Mark as stale
+        t.get();
+
+        // Read only - same allocation
+        {
+            let v = t.make_mut();
+            assert_eq!(v.len(), 2);
+        }
+
+        assert!(Arc::ptr_eq(t.peek(), &shared));
+        assert!(!t.is_fresh());
+
+        // Written but ends up the same
+        {
+            let mut v = t.make_mut();
+            v[0] = 10;
+            v[0] = 1;
+        }
+
+        assert!(!t.is_fresh());
+        assert_eq!(**t.peek(), vec![1, 2]);
+    }
+
     #[test]
     fn test_get_if_fresh() {
         let mut t = TrackedRef::new("hello");
@@ -169,6 +565,78 @@ mod tests {
         assert!(v.is_none());
     }
 
+    #[test]
+    fn test_peek_or_some() {
+        let t: TrackedOption<i32> = TrackedRef::new(Some(5));
+
+        assert_eq!(t.peek_or(&0), &5);
+        assert!(t.is_fresh());
+    }
+
+    #[test]
+    fn test_peek_or_none() {
+        let mut t: TrackedOption<i32> = TrackedRef::new(None);
+
+        // Mark as stale
+        t.get();
+
+        assert_eq!(t.peek_or(&7), &7);
+        assert!(!t.is_fresh());
+    }
+
+    #[test]
+    fn test_set_if_absent() {
+        let mut t: TrackedOption<i32> = TrackedRef::new(None);
+
+        // Mark as stale
+        t.get();
+
+        assert!(t.set_if_absent(5));
+        assert!(t.is_fresh());
+        assert_eq!(t.peek(), &Some(5));
+    }
+
+    #[test]
+    fn test_set_if_absent_present() {
+        let mut t: TrackedOption<i32> = TrackedRef::new(Some(5));
+
+        // Mark as stale
+        t.get();
+
+        assert!(!t.set_if_absent(6));
+        assert!(!t.is_fresh());
+        assert_eq!(t.peek(), &Some(5));
+    }
+
+    #[test]
+    fn test_get_ok_if_fresh() {
+        let mut t: TrackedRef<Result<i32, &str>> = TrackedRef::new(Ok(5));
+
+        assert!(t.get_err_if_fresh().is_none());
+        assert_eq!(t.get_ok_if_fresh(), Some(&5));
+        assert!(!t.is_fresh());
+    }
+
+    #[test]
+    fn test_get_err_if_fresh() {
+        let mut t: TrackedRef<Result<i32, &str>> = TrackedRef::new(Err("oops"));
+
+        assert!(t.get_ok_if_fresh().is_none());
+        assert_eq!(t.get_err_if_fresh(), Some(&"oops"));
+        assert!(!t.is_fresh());
+    }
+
+    #[test]
+    fn test_get_result_if_fresh_stale() {
+        let mut t: TrackedRef<Result<i32, &str>> = TrackedRef::new(Ok(5));
+
+        // Mark as stale
+        t.get();
+
+        assert!(t.get_ok_if_fresh().is_none());
+        assert!(t.get_err_if_fresh().is_none());
+    }
+
     #[test]
     fn test_peek() {
         let t = TrackedRef::new(777);
@@ -179,6 +647,83 @@ mod tests {
         assert_eq!(v, &777);
     }
 
+    #[test]
+    fn test_as_ref() {
+        let mut t = TrackedRef::new(String::from("hello"));
+
+        // Mark as stale
+        t.get();
+
+        let view = t.as_ref();
+
+        assert!(!view.is_fresh());
+        assert_eq!(*view.peek(), "hello");
+    }
+
+    #[test]
+    fn test_to_owned_tracked() {
+        let owned = String::from("hello");
+        let view: TrackedRef<&str> = TrackedRef::new(owned.as_str());
+
+        let t = view.to_owned_tracked();
+
+        assert!(t.is_fresh());
+        assert_eq!(t.peek(), "hello");
+
+        let mut t = TrackedRef::new(owned.clone());
+
+        // Mark as stale
+        t.get();
+
+        let t = t.as_ref().to_owned_tracked();
+
+        assert!(!t.is_fresh());
+        assert_eq!(t.peek(), &owned);
+    }
+
+    #[test]
+    fn test_snapshot_restore() {
+        let mut t = TrackedRef::new(5);
+
+        // Mark as stale
+        t.get();
+
+        let snap = t.snapshot();
+
+        t.set(6);
+
+        assert!(t.is_fresh());
+
+        t.restore(snap);
+
+        assert!(!t.is_fresh());
+        assert_eq!(t.peek(), &5);
+    }
+
+    #[test]
+    fn test_compare_snapshots() {
+        let mut t = TrackedRef::new(5);
+        let a = t.snapshot();
+        let b = t.snapshot();
+
+        assert!(snapshots_equal(&a, &b));
+        assert_eq!(snapshot_value(&a), 5);
+
+        // Same value, different freshness
+        t.get();
+
+        let c = t.snapshot();
+
+        assert!(!snapshots_equal(&a, &c));
+
+        t.set(6);
+
+        let d = t.snapshot();
+
+        assert!(!snapshots_equal(&a, &d));
+        assert_eq!(snapshot_value(&d), 6);
+    }
+
     #[test]
     fn test_is_fresh() {
         let t = TrackedRef {
@@ -198,5 +743,23 @@ mod tests {
 
         assert!(!t.is_fresh());
     }
-}
 
+    #[test]
+    fn test_debug_assert_stale() {
+        let mut t = TrackedRef::new(5);
+
+        // Mark as stale
+        t.get();
+
+        t.debug_assert_stale();
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic]
+    fn test_debug_assert_stale_fresh() {
+        let t = TrackedRef::new(5);
+
+        t.debug_assert_stale();
+    }
+}
@@ -0,0 +1,123 @@
+/// Tracked float that only reports relative changes beyond a tolerance.
+///
+/// A set is marked fresh when `|new - old| / max(|old|, epsilon)` exceeds the
+/// relative tolerance, so a 1% change counts the same at 10 as at 10000. The
+/// `epsilon` floor keeps the comparison well defined around zero.
+#[derive(Copy, Clone)]
+pub struct TrackedRelative {
+    fresh: bool,
+    val: f64,
+    tolerance: f64,
+    epsilon: f64
+}
+
+impl TrackedRelative {
+    pub fn new(val: f64, tolerance: f64, epsilon: f64) -> Self {
+        TrackedRelative {
+            fresh: true,
+            val,
+            tolerance,
+            epsilon
+        }
+    }
+
+    /// Set a new value, marked as fresh if it differs from the existing value by more than the
+    /// relative tolerance.
+    ///
+    /// Smaller changes are discarded, so the next set is still compared against the old value.
+    /// Returns whether the value was stored.
+    pub fn set(&mut self, val: f64) -> bool {
+        let scale = self.val.abs().max(self.epsilon);
+
+        if (val - self.val).abs() / scale > self.tolerance {
+            self.val = val;
+            self.fresh = true;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Get the current value, marking it as stale.
+    pub fn get(&mut self) -> f64 {
+        self.fresh = false;
+        self.val
+    }
+
+    /// Get the current value if it's been modified since last time we checked, marking it as stale.
+    pub fn get_if_fresh(&mut self) -> Option<f64> {
+        if self.fresh {
+            Some(self.get())
+        } else {
+            None
+        }
+    }
+
+    // Get the current value without marking it.
+    pub fn peek(&self) -> f64 {
+        self.val
+    }
+
+    pub fn is_fresh(&self) -> bool {
+        self.fresh
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new() {
+        let t = TrackedRelative::new(10.0, 0.01, 1e-9);
+
+        assert!(t.is_fresh());
+        assert_eq!(t.peek(), 10.0);
+    }
+
+    #[test]
+    fn test_set_across_magnitudes() {
+        for &base in &[10.0, 10000.0, 0.001] {
+            let mut t = TrackedRelative::new(base, 0.01, 1e-9);
+
+            // Reset to stale
+            t.get();
+
+            // Half a percent - still stale
+            assert!(!t.set(base * 1.005));
+            assert!(!t.is_fresh());
+            assert_eq!(t.peek(), base);
+
+            // Two percent - fresh again
+            assert!(t.set(base * 1.02));
+            assert!(t.is_fresh());
+            assert_eq!(t.peek(), base * 1.02);
+        }
+    }
+
+    #[test]
+    fn test_set_small_changes_accumulate() {
+        let mut t = TrackedRelative::new(100.0, 0.01, 1e-9);
+
+        // Reset to stale
+        t.get();
+
+        // Each step is compared against the last stored value
+        assert!(!t.set(100.6));
+        assert!(t.set(101.2));
+        assert!(t.is_fresh());
+    }
+
+    #[test]
+    fn test_set_from_zero() {
+        let mut t = TrackedRelative::new(0.0, 0.01, 1.0);
+
+        // Reset to stale
+        t.get();
+
+        // Relative to the epsilon floor when the old value is zero
+        assert!(!t.set(0.005));
+        assert!(t.set(0.5));
+        assert!(t.is_fresh());
+    }
+}
@@ -0,0 +1,118 @@
+/// Tracked value that reports a sample of its changes to a logging callback.
+///
+/// The callback is invoked with the new value on the 1st, `N+1`th, `2N+1`th,
+/// etc. value-changing set. Sets that don't change the value are never counted.
+pub struct TrackedSampled<T> {
+    fresh: bool,
+    val: T,
+    every: u64,
+    changes: u64,
+    log: Box<dyn FnMut(&T)>
+}
+
+impl<T: PartialEq> TrackedSampled<T> {
+    /// Create a new wrapper that logs every `every`th change.
+    ///
+    /// Panics if `every` is zero.
+    pub fn new<F>(val: T, every: u64, log: F) -> Self
+        where F: FnMut(&T) + 'static
+    {
+        assert!(every > 0, "sampling interval must be non-zero");
+
+        TrackedSampled {
+            fresh: true,
+            val,
+            every,
+            changes: 0,
+            log: Box::new(log)
+        }
+    }
+
+    /// Set a new value, marked as fresh if not equal to the existing value.
+    pub fn set(&mut self, val: T) {
+        if self.val != val {
+            self.val = val;
+            self.fresh = true;
+
+            if self.changes.is_multiple_of(self.every) {
+                (self.log)(&self.val);
+            }
+
+            self.changes = self.changes.wrapping_add(1);
+        }
+    }
+
+    /// Get a reference to the current value, marking it as stale.
+    pub fn get(&mut self) -> &T {
+        self.fresh = false;
+        &self.val
+    }
+
+    /// Get the current value if it's been modified since last time we checked, marking it as stale.
+    pub fn get_if_fresh(&mut self) -> Option<&T> {
+        if self.fresh {
+            Some(self.get())
+        } else {
+            None
+        }
+    }
+
+    // Get the current value without marking it.
+    pub fn peek(&self) -> &T {
+        &self.val
+    }
+
+    pub fn is_fresh(&self) -> bool {
+        self.fresh
+    }
+
+    /// Number of value-changing sets so far.
+    pub fn changes(&self) -> u64 {
+        self.changes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_sampling() {
+        let logged = Rc::new(RefCell::new(Vec::new()));
+        let sink = logged.clone();
+        let mut t = TrackedSampled::new(0, 3, move |v: &i32| sink.borrow_mut().push(*v));
+
+        for v in 1..8 {
+            t.set(v);
+        }
+
+        // Changes 1, 4 and 7
+        assert_eq!(*logged.borrow(), vec![1, 4, 7]);
+        assert_eq!(t.changes(), 7);
+    }
+
+    #[test]
+    fn test_same_val_not_counted() {
+        let logged = Rc::new(RefCell::new(Vec::new()));
+        let sink = logged.clone();
+        let mut t = TrackedSampled::new(0, 2, move |v: &i32| sink.borrow_mut().push(*v));
+
+        t.set(0);
+        t.set(1);
+        t.set(1);
+        t.set(1);
+        t.set(2);
+        t.set(3);
+
+        assert_eq!(*logged.borrow(), vec![1, 3]);
+        assert_eq!(t.changes(), 3);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_zero_interval() {
+        TrackedSampled::new(0, 0, |_: &i32| {});
+    }
+}
@@ -0,0 +1,131 @@
+/// Tracked value that rejects out-of-order updates.
+///
+/// Each update carries a caller-supplied sequence number, and is only applied
+/// if it is newer than the last accepted one.
+pub struct TrackedSeq<T> {
+    fresh: bool,
+    val: T,
+    seq: Option<u64>
+}
+
+impl<T: PartialEq> TrackedSeq<T> {
+    /// Create a new wrapper around `val`. The first update is accepted with any sequence number.
+    pub fn new(val: T) -> Self {
+        TrackedSeq {
+            fresh: true,
+            val,
+            seq: None
+        }
+    }
+
+    /// Set a new value if `seq` is greater than the last accepted sequence number, marked as
+    /// fresh if not equal to the existing value.
+    ///
+    /// Returns whether the update was accepted, even if it didn't change the value.
+    pub fn set(&mut self, val: T, seq: u64) -> bool {
+        match self.seq {
+            Some(last) if seq <= last => false,
+            _ => {
+                self.seq = Some(seq);
+
+                if self.val != val {
+                    self.val = val;
+                    self.fresh = true;
+                }
+
+                true
+            }
+        }
+    }
+
+    /// Get a reference to the current value, marking it as stale.
+    pub fn get(&mut self) -> &T {
+        self.fresh = false;
+        &self.val
+    }
+
+    /// Get the current value if it's been modified since last time we checked, marking it as stale.
+    pub fn get_if_fresh(&mut self) -> Option<&T> {
+        if self.fresh {
+            Some(self.get())
+        } else {
+            None
+        }
+    }
+
+    // Get the current value without marking it.
+    pub fn peek(&self) -> &T {
+        &self.val
+    }
+
+    pub fn is_fresh(&self) -> bool {
+        self.fresh
+    }
+
+    /// Last accepted sequence number, if any.
+    pub fn seq(&self) -> Option<u64> {
+        self.seq
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new() {
+        let t = TrackedSeq::new(5);
+
+        assert!(t.is_fresh());
+        assert_eq!(t.peek(), &5);
+        assert_eq!(t.seq(), None);
+    }
+
+    #[test]
+    fn test_set_in_order() {
+        let mut t = TrackedSeq::new(5);
+
+        // Reset to stale
+        t.get();
+
+        assert!(t.set(6, 1));
+        assert!(t.set(7, 2));
+
+        assert!(t.is_fresh());
+        assert_eq!(t.peek(), &7);
+        assert_eq!(t.seq(), Some(2));
+    }
+
+    #[test]
+    fn test_set_out_of_order() {
+        let mut t = TrackedSeq::new(5);
+
+        t.set(7, 2);
+
+        // Reset to stale
+        t.get();
+
+        // Older update - rejected
+        assert!(!t.set(6, 1));
+
+        assert!(!t.is_fresh());
+        assert_eq!(t.peek(), &7);
+        assert_eq!(t.seq(), Some(2));
+    }
+
+    #[test]
+    fn test_set_equal_seq() {
+        let mut t = TrackedSeq::new(5);
+
+        t.set(7, 2);
+
+        // Reset to stale
+        t.get();
+
+        // Duplicate sequence number - rejected
+        assert!(!t.set(8, 2));
+
+        assert!(!t.is_fresh());
+        assert_eq!(t.peek(), &7);
+    }
+}
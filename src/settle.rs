@@ -0,0 +1,122 @@
+/// Tracked value that is only reported once input has settled.
+///
+/// Each `set` records the latest value and restarts the settle count. After
+/// `polls` calls to `poll` without a new set, the pending value is settled and
+/// `get_if_fresh` returns it, provided it differs from the last value returned.
+pub struct TrackedSettle<T> {
+    last: T,
+    pending: Option<T>,
+    polls: u32,
+    quiet: u32
+}
+
+impl<T: PartialEq> TrackedSettle<T> {
+    /// Create a new wrapper around `val` that settles after `polls` quiet polls.
+    ///
+    /// The initial value counts as already read.
+    pub fn new(val: T, polls: u32) -> Self {
+        TrackedSettle {
+            last: val,
+            pending: None,
+            polls,
+            quiet: 0
+        }
+    }
+
+    /// Record a new value, restarting the settle count.
+    pub fn set(&mut self, val: T) {
+        self.pending = Some(val);
+        self.quiet = 0;
+    }
+
+    /// Advance the settle count by one.
+    pub fn poll(&mut self) {
+        if self.pending.is_some() {
+            self.quiet = self.quiet.saturating_add(1);
+        }
+    }
+
+    pub fn is_settled(&self) -> bool {
+        self.quiet >= self.polls
+    }
+
+    /// Get the settled value if it differs from the last value returned.
+    ///
+    /// Returns `None` while input is still settling.
+    pub fn get_if_fresh(&mut self) -> Option<&T> {
+        if !self.is_settled() {
+            return None;
+        }
+
+        match self.pending.take() {
+            Some(val) => {
+                if val != self.last {
+                    self.last = val;
+                    Some(&self.last)
+                } else {
+                    None
+                }
+            }
+            None => None
+        }
+    }
+
+    // Get the last returned value without touching the pending value.
+    pub fn peek(&self) -> &T {
+        &self.last
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new() {
+        let mut t = TrackedSettle::new("", 2);
+
+        assert_eq!(t.peek(), &"");
+        assert!(t.get_if_fresh().is_none());
+    }
+
+    #[test]
+    fn test_typing_then_pause() {
+        let mut t = TrackedSettle::new("", 2);
+        let mut emitted = Vec::new();
+
+        // Keystrokes arrive faster than the settle window
+        for text in &["h", "he", "hel", "hell", "hello"] {
+            t.set(*text);
+            t.poll();
+
+            if let Some(v) = t.get_if_fresh() {
+                emitted.push(*v);
+            }
+        }
+
+        // Pause
+        for _ in 0..3 {
+            t.poll();
+
+            if let Some(v) = t.get_if_fresh() {
+                emitted.push(*v);
+            }
+        }
+
+        assert_eq!(emitted, vec!["hello"]);
+        assert_eq!(t.peek(), &"hello");
+    }
+
+    #[test]
+    fn test_settles_to_last_returned() {
+        let mut t = TrackedSettle::new("a", 1);
+
+        // Typed and deleted - nothing to report
+        t.set("ab");
+        t.set("a");
+        t.poll();
+
+        assert!(t.is_settled());
+        assert!(t.get_if_fresh().is_none());
+    }
+}
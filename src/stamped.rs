@@ -0,0 +1,108 @@
+use std::cmp;
+use std::ops::{Deref, DerefMut};
+use std::sync::atomic::{AtomicU64, Ordering};
+use value::Tracked;
+
+static NEXT_STAMP: AtomicU64 = AtomicU64::new(0);
+
+/// `Tracked` value that records when it last changed relative to other stamped values.
+///
+/// Each value-changing `set` takes a stamp from a process-wide counter, so use this instead of
+/// `Tracked` only where `cmp_recency` is needed. Derefs to the inner `Tracked` for everything
+/// else; only `set` takes a new stamp.
+#[derive(Copy, Clone)]
+pub struct TrackedStamped<T: PartialEq + Copy> {
+    tracked: Tracked<T>,
+    stamp: u64
+}
+
+impl<T: PartialEq + Copy> TrackedStamped<T> {
+    pub fn new(val: T) -> Self {
+        TrackedStamped {
+            tracked: Tracked::new(val),
+            stamp: NEXT_STAMP.fetch_add(1, Ordering::Relaxed)
+        }
+    }
+
+    /// Set a new value, marked as fresh and stamped if not equal to the existing value.
+    pub fn set(&mut self, val: T) {
+        if self.tracked.peek() != val {
+            self.tracked.set(val);
+            self.stamp = NEXT_STAMP.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+impl<T: PartialEq + Copy> Deref for TrackedStamped<T> {
+    type Target = Tracked<T>;
+
+    fn deref(&self) -> &Tracked<T> {
+        &self.tracked
+    }
+}
+
+impl<T: PartialEq + Copy> DerefMut for TrackedStamped<T> {
+    fn deref_mut(&mut self) -> &mut Tracked<T> {
+        &mut self.tracked
+    }
+}
+
+/// Compare which of two values changed more recently.
+///
+/// Uses the stamp taken at construction and at every value-changing set. `Greater` means `a`
+/// changed more recently than `b`.
+pub fn cmp_recency<T, U>(a: &TrackedStamped<T>, b: &TrackedStamped<U>) -> cmp::Ordering
+    where T: PartialEq + Copy,
+          U: PartialEq + Copy
+{
+    a.stamp.cmp(&b.stamp)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new() {
+        let t = TrackedStamped::new(5);
+
+        assert!(t.is_fresh());
+        assert_eq!(t.peek(), 5);
+    }
+
+    #[test]
+    fn test_set() {
+        let mut t = TrackedStamped::new(5);
+        let start = t.generation();
+
+        // Reset to stale
+        t.get();
+
+        t.set(6);
+
+        assert!(t.is_fresh());
+        assert_eq!(t.generations_elapsed(start), 1);
+    }
+
+    #[test]
+    fn test_cmp_recency() {
+        let mut a = TrackedStamped::new(1);
+        let mut b = TrackedStamped::new(1.0);
+
+        assert_eq!(cmp_recency(&a, &b), cmp::Ordering::Less);
+
+        a.set(2);
+
+        assert_eq!(cmp_recency(&a, &b), cmp::Ordering::Greater);
+
+        // Same value - doesn't count as an update
+        b.set(1.0);
+
+        assert_eq!(cmp_recency(&a, &b), cmp::Ordering::Greater);
+
+        b.set(3.0);
+
+        assert_eq!(cmp_recency(&a, &b), cmp::Ordering::Less);
+        assert_eq!(cmp_recency(&a, &a), cmp::Ordering::Equal);
+    }
+}
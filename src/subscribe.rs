@@ -0,0 +1,140 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Weak};
+
+/// Notification flag held by a subscriber of a `TrackedNotify`.
+#[derive(Debug, Default)]
+pub struct SubscriptionState {
+    pending: AtomicBool
+}
+
+impl SubscriptionState {
+    /// Whether a change has happened since the notification was last taken.
+    pub fn is_pending(&self) -> bool {
+        self.pending.load(Ordering::SeqCst)
+    }
+
+    /// Clear the notification, returning whether there was one.
+    pub fn take(&self) -> bool {
+        self.pending.swap(false, Ordering::SeqCst)
+    }
+}
+
+/// Tracked value that notifies weakly held subscribers of changes.
+///
+/// The wrapper only keeps a `Weak` reference to each subscription, so a
+/// subscriber unsubscribes by dropping its `Arc`. Dead subscriptions are pruned
+/// on the next change.
+pub struct TrackedNotify<T> {
+    fresh: bool,
+    val: T,
+    subscribers: Vec<Weak<SubscriptionState>>
+}
+
+impl<T: PartialEq> TrackedNotify<T> {
+    pub fn new(val: T) -> Self {
+        TrackedNotify {
+            fresh: true,
+            val,
+            subscribers: Vec::new()
+        }
+    }
+
+    /// Subscribe to changes. The subscription lasts until the returned `Arc` is dropped.
+    pub fn subscribe_weak(&mut self) -> Arc<SubscriptionState> {
+        let state = Arc::new(SubscriptionState::default());
+        self.subscribers.push(Arc::downgrade(&state));
+        state
+    }
+
+    /// Set a new value, marked as fresh and notifying live subscribers if not equal to the
+    /// existing value.
+    pub fn set(&mut self, val: T) {
+        if self.val != val {
+            self.val = val;
+            self.fresh = true;
+
+            self.subscribers.retain(|weak| match weak.upgrade() {
+                Some(state) => {
+                    state.pending.store(true, Ordering::SeqCst);
+                    true
+                }
+                None => false
+            });
+        }
+    }
+
+    /// Number of subscriptions held, including dropped ones not yet pruned.
+    pub fn subscriber_count(&self) -> usize {
+        self.subscribers.len()
+    }
+
+    /// Get a reference to the current value, marking it as stale.
+    pub fn get(&mut self) -> &T {
+        self.fresh = false;
+        &self.val
+    }
+
+    /// Get the current value if it's been modified since last time we checked, marking it as stale.
+    pub fn get_if_fresh(&mut self) -> Option<&T> {
+        if self.fresh {
+            Some(self.get())
+        } else {
+            None
+        }
+    }
+
+    // Get the current value without marking it.
+    pub fn peek(&self) -> &T {
+        &self.val
+    }
+
+    pub fn is_fresh(&self) -> bool {
+        self.fresh
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_notify() {
+        let mut t = TrackedNotify::new(5);
+        let a = t.subscribe_weak();
+        let b = t.subscribe_weak();
+
+        assert!(!a.is_pending());
+
+        t.set(6);
+
+        assert!(a.take());
+        assert!(!a.take());
+        assert!(b.is_pending());
+
+        // Same value - no notification
+        t.set(6);
+
+        assert!(!a.is_pending());
+    }
+
+    #[test]
+    fn test_prune_dropped() {
+        let mut t = TrackedNotify::new(5);
+        let a = t.subscribe_weak();
+        let b = t.subscribe_weak();
+
+        drop(b);
+
+        assert_eq!(t.subscriber_count(), 2);
+
+        t.set(6);
+
+        assert_eq!(t.subscriber_count(), 1);
+        assert!(a.take());
+
+        drop(a);
+        t.set(7);
+
+        assert_eq!(t.subscriber_count(), 0);
+    }
+}
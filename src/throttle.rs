@@ -0,0 +1,110 @@
+use clock::{Clock, SystemClock};
+use std::time::{Duration, Instant};
+
+/// Tracked value that is reported at most once per interval.
+///
+/// Sets are applied as usual, but `get_if_fresh` holds back a fresh value until
+/// `interval` has passed since the last value was returned.
+pub struct TrackedThrottle<T, C = SystemClock> {
+    fresh: bool,
+    val: T,
+    interval: Duration,
+    last_read: Option<Instant>,
+    clock: C
+}
+
+impl<T: PartialEq> TrackedThrottle<T> {
+    pub fn new(val: T, interval: Duration) -> Self {
+        TrackedThrottle::new_with_clock(val, interval, SystemClock)
+    }
+}
+
+impl<T: PartialEq, C: Clock> TrackedThrottle<T, C> {
+    pub fn new_with_clock(val: T, interval: Duration, clock: C) -> Self {
+        TrackedThrottle {
+            fresh: true,
+            val,
+            interval,
+            last_read: None,
+            clock
+        }
+    }
+
+    /// Set a new value, marked as fresh if not equal to the existing value.
+    pub fn set(&mut self, val: T) {
+        if self.val != val {
+            self.val = val;
+            self.fresh = true;
+        }
+    }
+
+    /// Get the current value if it's been modified since last time we checked and the interval
+    /// has passed since then, marking it as stale.
+    pub fn get_if_fresh(&mut self) -> Option<&T> {
+        let now = self.clock.now();
+        let ready = match self.last_read {
+            Some(last) => now.duration_since(last) >= self.interval,
+            None => true
+        };
+
+        if self.fresh && ready {
+            self.fresh = false;
+            self.last_read = Some(now);
+            Some(&self.val)
+        } else {
+            None
+        }
+    }
+
+    // Get the current value without marking it.
+    pub fn peek(&self) -> &T {
+        &self.val
+    }
+
+    pub fn is_fresh(&self) -> bool {
+        self.fresh
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clock::MockClock;
+
+    #[test]
+    fn test_new() {
+        let t = TrackedThrottle::new(5, Duration::from_millis(100));
+
+        assert!(t.is_fresh());
+        assert_eq!(t.peek(), &5);
+    }
+
+    #[test]
+    fn test_throttle() {
+        let clock = MockClock::new();
+        let mut t = TrackedThrottle::new_with_clock(5, Duration::from_millis(100), clock.clone());
+
+        assert_eq!(t.get_if_fresh(), Some(&5));
+
+        // Within the interval - held back
+        t.set(6);
+        clock.advance(Duration::from_millis(50));
+
+        assert!(t.get_if_fresh().is_none());
+        assert!(t.is_fresh());
+
+        // Interval passed - reported
+        clock.advance(Duration::from_millis(50));
+
+        assert_eq!(t.get_if_fresh(), Some(&6));
+
+        // Interval passed but nothing changed
+        clock.advance(Duration::from_millis(200));
+
+        assert!(t.get_if_fresh().is_none());
+
+        t.set(7);
+
+        assert_eq!(t.get_if_fresh(), Some(&7));
+    }
+}
@@ -0,0 +1,101 @@
+use reference::TrackedRef;
+use std::any;
+use value::Tracked;
+
+/// Freshness interface shared by tracked wrappers, usable as a trait object.
+///
+/// Lets values of different types be held in one collection, e.g.
+/// `Vec<Box<dyn TrackedValue>>`, and polled uniformly.
+pub trait TrackedValue {
+    fn is_fresh(&self) -> bool;
+
+    /// Mark the value as stale, returning whether it was fresh.
+    fn consume(&mut self) -> bool;
+
+    /// Name of the wrapped value's type, for diagnostics.
+    fn type_name(&self) -> &'static str;
+}
+
+impl<T: PartialEq + Copy> TrackedValue for Tracked<T> {
+    fn is_fresh(&self) -> bool {
+        Tracked::is_fresh(self)
+    }
+
+    fn consume(&mut self) -> bool {
+        self.get_if_fresh().is_some()
+    }
+
+    fn type_name(&self) -> &'static str {
+        any::type_name::<T>()
+    }
+}
+
+impl<T> TrackedValue for TrackedRef<T> {
+    fn is_fresh(&self) -> bool {
+        TrackedRef::is_fresh(self)
+    }
+
+    fn consume(&mut self) -> bool {
+        self.get_if_fresh().is_some()
+    }
+
+    fn type_name(&self) -> &'static str {
+        any::type_name::<T>()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reference::TrackedRefSet;
+
+    #[test]
+    fn test_heterogeneous() {
+        let mut values: Vec<Box<dyn TrackedValue>> = vec![
+            Box::new(Tracked::new(1)),
+            Box::new(TrackedRef::new(String::from("two"))),
+            Box::new(Tracked::new(3.0))
+        ];
+
+        values[1].consume();
+
+        let fresh: Vec<_> = values.iter().map(|v| v.is_fresh()).collect();
+
+        assert_eq!(fresh, vec![true, false, true]);
+
+        let consumed: Vec<_> = values.iter_mut().map(|v| v.consume()).collect();
+
+        assert_eq!(consumed, vec![true, false, true]);
+        assert!(values.iter().all(|v| !v.is_fresh()));
+    }
+
+    #[test]
+    fn test_type_name() {
+        let a = Tracked::new(1i32);
+        let b = TrackedRef::new(String::new());
+
+        assert_eq!(a.as_dyn_ref().type_name(), "i32");
+        assert!(b.as_dyn_ref().type_name().ends_with("String"));
+    }
+
+    #[test]
+    fn test_as_dyn() {
+        let mut a = Tracked::new(1);
+        let mut b = TrackedRef::new(vec![2]);
+
+        {
+            let mut values: Vec<&mut dyn TrackedValue> = vec![a.as_dyn(), b.as_dyn()];
+
+            for v in values.iter_mut() {
+                v.consume();
+            }
+        }
+
+        assert!(!a.is_fresh());
+        assert!(!b.is_fresh());
+
+        b.set(vec![3]);
+
+        assert!(b.as_dyn().consume());
+    }
+}
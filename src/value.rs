@@ -1,15 +1,51 @@
+use error::RangeError;
+use std::fmt;
+use std::ops::{Add, BitAnd, BitOr, Not, Rem};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tracked_value::TrackedValue;
+
+static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
 /// Tracked value for Copy types.
 #[derive(Copy, Clone)]
 pub struct Tracked<T: PartialEq + Copy> {
     fresh: bool,
-    val: T
+    val: T,
+    id: usize,
+    generation: u64
+}
+
+/// Token identifying how many times a `Tracked` value has changed.
+///
+/// Tokens are tied to the value they were taken from, and checked against it in debug builds.
+/// Copies of a `Tracked` share its identity.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Generation {
+    id: usize,
+    count: u64
+}
+
+/// Result of reading a `Tracked` value as a signal.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Signal<T> {
+    Changed(T),
+    Unchanged
+}
+
+/// Decision returned by the hook passed to `Tracked::set_with`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum HookAction {
+    Accept,
+    Reject
 }
 
 impl<T: PartialEq + Copy> Tracked<T> {
     pub fn new(val: T) -> Self {
         Tracked {
             fresh: true,
-            val
+            val,
+            id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+            generation: 0
         }
     }
 
@@ -18,9 +54,62 @@ impl<T: PartialEq + Copy> Tracked<T> {
         if self.val != val {
             self.val = val;
             self.fresh = true;
+            self.generation = self.generation.wrapping_add(1);
+        }
+    }
+
+    /// Set a new value if `hook` accepts it, marked as fresh if not equal to the existing value.
+    ///
+    /// The hook is called with the old and new values only when they differ. Returns whether
+    /// the value changed.
+    pub fn set_with<F>(&mut self, val: T, hook: F) -> bool
+        where F: FnOnce(&T, &T) -> HookAction
+    {
+        if self.val != val && hook(&self.val, &val) == HookAction::Accept {
+            self.set(val);
+            true
+        } else {
+            false
         }
     }
 
+    /// Drain a sequence of updates, keeping only the last one.
+    ///
+    /// Freshness reflects the net change: if the final value equals the value before the call,
+    /// nothing is marked, however much the intermediate values differed. Returns whether the
+    /// value changed.
+    pub fn set_from_iter<I: IntoIterator<Item = T>>(&mut self, iter: I) -> bool {
+        match iter.into_iter().last() {
+            Some(val) => {
+                let changed = self.val != val;
+                self.set(val);
+                changed
+            }
+            None => false
+        }
+    }
+
+    /// Set a new value only if the current one has already been read, returning the old value.
+    ///
+    /// If the current value is still fresh it is left in place and `None` is returned, so an
+    /// unread value is never clobbered. Otherwise the new value is applied as with `set`.
+    pub fn replace_stale(&mut self, val: T) -> Option<T> {
+        if self.fresh {
+            None
+        } else {
+            let old = self.val;
+            self.set(val);
+            Some(old)
+        }
+    }
+
+    /// Replace the value with `f(value)` without marking it or advancing the generation.
+    ///
+    /// Meant for normalizing the representation of a value; consumers won't see it as a change.
+    pub fn remap<F: FnOnce(T) -> T>(&mut self, f: F) {
+        self.val = f(self.val);
+    }
+
     /// Get the current value, marking it as stale.
     pub fn get(&mut self) -> T {
         self.fresh = false;
@@ -36,6 +125,33 @@ impl<T: PartialEq + Copy> Tracked<T> {
         }
     }
 
+    /// Read the value as a signal, marking it as stale.
+    ///
+    /// Equivalent to `get_if_fresh`, but reads better when matched on in signal-processing code.
+    pub fn as_signal(&mut self) -> Signal<T> {
+        match self.get_if_fresh() {
+            Some(val) => Signal::Changed(val),
+            None => Signal::Unchanged
+        }
+    }
+
+    /// Feed each input through `set`, yielding only the values that changed the stored value.
+    ///
+    /// Consecutive duplicates are filtered out. Freshness is left as `set` leaves it.
+    pub fn changes<'a, I>(&'a mut self, inputs: I) -> impl Iterator<Item = T> + 'a
+        where I: IntoIterator<Item = T>,
+              I::IntoIter: 'a
+    {
+        inputs.into_iter().filter(move |&val| {
+            if self.val != val {
+                self.set(val);
+                true
+            } else {
+                false
+            }
+        })
+    }
+
     // Get the current value without marking it.
     pub fn peek(&self) -> T {
         self.val
@@ -44,7 +160,184 @@ impl<T: PartialEq + Copy> Tracked<T> {
     pub fn is_fresh(&self) -> bool {
         self.fresh
     }
+
+    /// Get a token for the current generation, which advances on every value-changing set.
+    pub fn generation(&self) -> Generation {
+        Generation {
+            id: self.id,
+            count: self.generation
+        }
+    }
+
+    /// Whether the value has changed since `since` was taken.
+    ///
+    /// Panics in debug builds if `since` was taken from a different value.
+    pub fn changed_since(&self, since: Generation) -> bool {
+        self.check_generation(since);
+        self.generation != since.count
+    }
+
+    /// Number of value-changing sets since `since` was taken.
+    ///
+    /// Uses wrapping arithmetic, so this stays correct across a wrap of the generation counter
+    /// as long as the consumer doesn't fall more than `u64::MAX / 2` changes behind. Panics in
+    /// debug builds if `since` was taken from a different value.
+    pub fn generations_elapsed(&self, since: Generation) -> u64 {
+        self.check_generation(since);
+        self.generation.wrapping_sub(since.count)
+    }
+
+    fn check_generation(&self, since: Generation) {
+        debug_assert!(since.id == self.id, "generation token belongs to a different tracked value");
+    }
+
+    /// View as a `TrackedValue` trait object.
+    pub fn as_dyn(&mut self) -> &mut dyn TrackedValue {
+        self
+    }
+
+    /// View as a read-only `TrackedValue` trait object.
+    pub fn as_dyn_ref(&self) -> &dyn TrackedValue {
+        self
+    }
+
+    /// Panic if the value is still fresh, i.e. it should have been read by now.
+    ///
+    /// Only checked when `debug_assertions` are enabled.
+    pub fn debug_assert_stale(&self) {
+        debug_assert!(!self.fresh, "tracked value is unexpectedly fresh");
+    }
+}
+
+impl<T: PartialEq + PartialOrd + Copy> Tracked<T> {
+    /// Set a new value if it lies within `[min, max]`, marked as fresh if not equal to the
+    /// existing value.
+    ///
+    /// Returns whether the value changed, or a `RangeError` describing the rejected value, in
+    /// which case nothing is modified. Values that can't be compared with the bounds, such as
+    /// NaN, are rejected.
+    pub fn set_in_range(&mut self, val: T, min: T, max: T) -> Result<bool, RangeError<T>> {
+        if !(val >= min && val <= max) {
+            return Err(RangeError { value: val, min, max });
+        }
+
+        let changed = self.val != val;
+        self.set(val);
+        Ok(changed)
+    }
+
+    /// Set a new value only if it is greater than the existing value, marking it as fresh.
+    ///
+    /// Equal or smaller values are ignored. Returns whether the value was set.
+    pub fn set_if_growing(&mut self, val: T) -> bool {
+        if val > self.val {
+            self.set(val);
+            true
+        } else {
+            false
+        }
+    }
+}
+
+impl<T> Tracked<T>
+    where T: PartialEq + PartialOrd + Copy + Default + Rem<Output = T> + Add<Output = T>
+{
+    /// Set a new cyclic value, reduced into `[0, modulus)` before comparing and storing.
+    ///
+    /// Different representations of the same value (e.g. 370 and 10 degrees with a modulus of
+    /// 360) don't mark it as fresh. Returns whether the value changed.
+    ///
+    /// Panics if `modulus` is not positive, or if `val` is not finite (infinity or NaN can't be
+    /// reduced into the range).
+    pub fn set_wrapping(&mut self, val: T, modulus: T) -> bool {
+        assert!(modulus > T::default(), "modulus must be positive");
+
+        let mut val = val % modulus;
+
+        if val < T::default() {
+            val = val + modulus;
+
+            // A tiny negative float rounds up to the modulus itself, which is the same as zero
+            if val >= modulus {
+                val = T::default();
+            }
+        }
+
+        assert!(val >= T::default() && val < modulus, "value must be finite");
+
+        let changed = self.val != val;
+        self.set(val);
+        changed
+    }
+}
+
+impl<T> Tracked<T>
+    where T: PartialEq + Copy + BitAnd<Output = T> + BitOr<Output = T> + Not<Output = T>
+{
+    /// Overwrite only the bits selected by `mask` with the corresponding bits of `bits`, marked
+    /// as fresh if the result differs from the existing value.
+    ///
+    /// Returns whether the value changed.
+    pub fn set_masked(&mut self, bits: T, mask: T) -> bool {
+        let val = (self.val & !mask) | (bits & mask);
+        let changed = self.val != val;
+        self.set(val);
+        changed
+    }
+}
+
+/// Distance below which `lerp_toward` considers the target reached.
+const LERP_EPSILON: f64 = 1e-6;
+
+macro_rules! impl_lerp_toward {
+    ($t:ty) => {
+        impl Tracked<$t> {
+            /// Move the value a fraction `t` of the way toward `target`, marked as fresh if it
+            /// moved beyond a tiny epsilon.
+            ///
+            /// Smaller steps are still applied, but don't mark the value or advance the
+            /// generation. Once within the epsilon of `target` the value snaps to it exactly,
+            /// which always marks it. Returns whether it is still animating, i.e. hasn't reached
+            /// `target` yet.
+            pub fn lerp_toward(&mut self, target: $t, t: f64) -> bool {
+                let val = self.val + (target - self.val) * t as $t;
+
+                if ((target - val) as f64).abs() <= LERP_EPSILON {
+                    self.set(target);
+                } else if ((val - self.val) as f64).abs() > LERP_EPSILON {
+                    self.set(val);
+                } else {
+                    self.val = val;
+                }
+
+                self.val != target
+            }
+        }
+    };
+}
+
+impl_lerp_toward!(f32);
+impl_lerp_toward!(f64);
+
+impl<T: Default + Copy + PartialEq> Tracked<T> {
+    /// Reset to `T::default()`, marked as fresh if the value wasn't already the default.
+    ///
+    /// This is an ordinary `set`, so consumers see the reset as a change. It never forces the
+    /// value stale; an already-default value keeps whatever freshness it had.
+    pub fn clear(&mut self) {
+        self.set(T::default());
+    }
 }
+
+impl<T: PartialEq + Copy + fmt::Debug> fmt::Debug for Tracked<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Tracked")
+            .field("val", &self.val)
+            .field("fresh", &self.fresh)
+            .finish()
+    }
+}
+
 impl<T: Default + Copy + PartialEq> Default for Tracked<T> {
     fn default() -> Self {
         Tracked::new(T::default())
@@ -90,6 +383,351 @@ mod tests {
         assert_eq!(t.peek(), 6);
     }
 
+    #[test]
+    fn test_set_wrapping() {
+        let mut t = Tracked::new(10);
+
+        // Reset to stale
+        t.get();
+
+        // Same angle - still stale
+        assert!(!t.set_wrapping(370, 360));
+        assert!(!t.set_wrapping(-350, 360));
+
+        assert!(!t.is_fresh());
+        assert_eq!(t.peek(), 10);
+
+        assert!(t.set_wrapping(380, 360));
+
+        assert!(t.is_fresh());
+        assert_eq!(t.peek(), 20);
+    }
+
+    #[test]
+    fn test_set_wrapping_float() {
+        let mut t = Tracked::new(0.0);
+
+        assert!(t.set_wrapping(-90.0, 360.0));
+        assert_eq!(t.peek(), 270.0);
+    }
+
+    #[test]
+    fn test_set_wrapping_float_rounding() {
+        let mut t = Tracked::new(0.0);
+
+        // Reset to stale
+        t.get();
+
+        // -1e-20 + 360 rounds to 360, which wraps back to zero
+        assert!(!t.set_wrapping(-1e-20, 360.0));
+        assert!(!t.is_fresh());
+        assert_eq!(t.peek(), 0.0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_set_wrapping_zero_modulus() {
+        let mut t = Tracked::new(0);
+
+        t.set_wrapping(5, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "finite")]
+    fn test_set_wrapping_infinite() {
+        let mut t = Tracked::new(0.0);
+
+        t.set_wrapping(f64::INFINITY, 360.0);
+    }
+
+    #[test]
+    fn test_set_with_accept() {
+        let mut t = Tracked::new(5);
+
+        // Reset to stale
+        t.get();
+
+        let changed = t.set_with(6, |old, new| {
+            assert_eq!((*old, *new), (5, 6));
+            HookAction::Accept
+        });
+
+        assert!(changed);
+        assert!(t.is_fresh());
+        assert_eq!(t.peek(), 6);
+    }
+
+    #[test]
+    fn test_set_with_reject() {
+        let mut t = Tracked::new(5);
+
+        // Reset to stale
+        t.get();
+
+        assert!(!t.set_with(6, |_, _| HookAction::Reject));
+        assert!(!t.is_fresh());
+        assert_eq!(t.peek(), 5);
+
+        // Same value - hook isn't consulted
+        assert!(!t.set_with(5, |_, _| panic!("hook called for unchanged value")));
+    }
+
+    #[test]
+    fn test_set_from_iter() {
+        let mut t = Tracked::new(5);
+
+        // Reset to stale
+        t.get();
+
+        assert!(t.set_from_iter(vec![6, 7, 8]));
+        assert!(t.is_fresh());
+        assert_eq!(t.peek(), 8);
+    }
+
+    #[test]
+    fn test_set_from_iter_no_net_change() {
+        let mut t = Tracked::new(5);
+        let start = t.generation();
+
+        // Reset to stale
+        t.get();
+
+        // Wanders off and comes back
+        assert!(!t.set_from_iter(vec![6, 7, 5]));
+        assert!(!t.set_from_iter(Vec::new()));
+        assert!(!t.is_fresh());
+        assert!(!t.changed_since(start));
+    }
+
+    #[test]
+    fn test_replace_stale() {
+        let mut t = Tracked::new(5);
+
+        // Reset to stale
+        t.get();
+
+        assert_eq!(t.replace_stale(6), Some(5));
+        assert!(t.is_fresh());
+        assert_eq!(t.peek(), 6);
+    }
+
+    #[test]
+    fn test_replace_stale_fresh() {
+        let mut t = Tracked::new(5);
+
+        // Still fresh - not overwritten
+        assert_eq!(t.replace_stale(6), None);
+        assert!(t.is_fresh());
+        assert_eq!(t.peek(), 5);
+    }
+
+    #[test]
+    fn test_set_in_range() {
+        let mut t = Tracked::new(5);
+
+        // Reset to stale
+        t.get();
+
+        assert_eq!(t.set_in_range(5, 0, 10), Ok(false));
+        assert!(!t.is_fresh());
+
+        assert_eq!(t.set_in_range(10, 0, 10), Ok(true));
+        assert!(t.is_fresh());
+        assert_eq!(t.peek(), 10);
+    }
+
+    #[test]
+    fn test_set_in_range_out_of_bounds() {
+        let mut t = Tracked::new(5);
+
+        // Reset to stale
+        t.get();
+
+        let err = t.set_in_range(11, 0, 10).unwrap_err();
+
+        assert_eq!(err.value, 11);
+        assert_eq!(err.min, 0);
+        assert_eq!(err.max, 10);
+        assert!(t.set_in_range(-1, 0, 10).is_err());
+
+        assert!(!t.is_fresh());
+        assert_eq!(t.peek(), 5);
+    }
+
+    #[test]
+    fn test_set_in_range_nan() {
+        let mut t = Tracked::new(0.5);
+
+        // Reset to stale
+        t.get();
+
+        assert!(t.set_in_range(f64::NAN, 0.0, 1.0).is_err());
+        assert!(!t.is_fresh());
+        assert_eq!(t.peek(), 0.5);
+    }
+
+    #[test]
+    fn test_set_if_growing() {
+        let mut t = Tracked::new(5);
+
+        // Reset to stale
+        t.get();
+
+        assert!(t.set_if_growing(6));
+        assert!(t.is_fresh());
+        assert_eq!(t.peek(), 6);
+    }
+
+    #[test]
+    fn test_set_if_growing_ignored() {
+        let mut t = Tracked::new(5);
+
+        // Reset to stale
+        t.get();
+
+        // Equal and smaller values are ignored
+        assert!(!t.set_if_growing(5));
+        assert!(!t.set_if_growing(4));
+
+        assert!(!t.is_fresh());
+        assert_eq!(t.peek(), 5);
+    }
+
+    #[test]
+    fn test_set_masked() {
+        let mut t = Tracked::new(0b1010_0000u8);
+
+        // Reset to stale
+        t.get();
+
+        // Only the low nibble is written
+        assert!(t.set_masked(0b1111_0101, 0b0000_1111));
+        assert!(t.is_fresh());
+        assert_eq!(t.peek(), 0b1010_0101);
+    }
+
+    #[test]
+    fn test_set_masked_same_bits() {
+        let mut t = Tracked::new(0b1010_0101u8);
+
+        // Reset to stale
+        t.get();
+
+        // Masked bits already match
+        assert!(!t.set_masked(0b0000_0101, 0b0000_1111));
+        assert!(!t.is_fresh());
+        assert_eq!(t.peek(), 0b1010_0101);
+    }
+
+    #[test]
+    fn test_lerp_toward() {
+        let mut t = Tracked::new(0.0f64);
+
+        // Reset to stale
+        t.get();
+
+        assert!(t.lerp_toward(10.0, 0.5));
+        assert!(t.is_fresh());
+        assert_eq!(t.get(), 5.0);
+
+        assert!(t.lerp_toward(10.0, 0.5));
+        assert_eq!(t.get(), 7.5);
+
+        // Converges and stops exactly on the target
+        let mut steps = 0;
+
+        while t.lerp_toward(10.0, 0.5) {
+            steps += 1;
+            assert!(steps < 100);
+        }
+
+        assert_eq!(t.get(), 10.0);
+
+        // Already there - nothing moves
+        assert!(!t.lerp_toward(10.0, 0.5));
+        assert!(!t.is_fresh());
+    }
+
+    #[test]
+    fn test_lerp_toward_f32() {
+        let mut t = Tracked::new(1.0f32);
+
+        assert!(!t.lerp_toward(0.0, 1.0));
+        assert_eq!(t.peek(), 0.0);
+    }
+
+    #[test]
+    fn test_lerp_toward_small_t() {
+        let mut t = Tracked::new(0.0f64);
+
+        // Reset to stale
+        t.get();
+
+        // Moves 1e-11 - applied but not marked
+        assert!(t.lerp_toward(10.0, 1e-12));
+        assert!(!t.is_fresh());
+        assert!(t.peek() > 0.0);
+
+        // Steps shrink below the epsilon well before the target, but it still converges
+        let mut steps = 0;
+
+        while t.lerp_toward(10.0, 0.05) {
+            steps += 1;
+            assert!(steps < 1000);
+        }
+
+        assert!(t.is_fresh());
+        assert_eq!(t.get(), 10.0);
+    }
+
+    #[test]
+    fn test_clear() {
+        let mut t = Tracked::new(5);
+
+        // Reset to stale
+        t.get();
+
+        t.clear();
+
+        assert!(t.is_fresh());
+        assert_eq!(t.peek(), 0);
+    }
+
+    #[test]
+    fn test_clear_default() {
+        let mut t = Tracked::new(0);
+
+        // Reset to stale
+        t.get();
+
+        // Already default - still stale
+        t.clear();
+
+        assert!(!t.is_fresh());
+        assert_eq!(t.peek(), 0);
+    }
+
+    #[test]
+    fn test_remap() {
+        let mut t = Tracked::new(-0.0f64);
+        let start = t.generation();
+
+        // Reset to stale
+        t.get();
+
+        t.remap(|v| v + 0.0);
+
+        assert!(!t.is_fresh());
+        assert!(!t.changed_since(start));
+        assert!(t.peek().is_sign_positive());
+
+        t.remap(|v| v * 2.0 + 1.0);
+
+        assert_eq!(t.peek(), 1.0);
+        assert!(!t.is_fresh());
+        assert!(!t.changed_since(start));
+    }
+
     #[test]
     fn test_get() {
         let mut t = Tracked::new(5);
@@ -123,6 +761,39 @@ mod tests {
         assert!(v.is_none());
     }
 
+    #[test]
+    fn test_as_signal() {
+        let mut t = Tracked::new(5);
+
+        assert_eq!(t.as_signal(), Signal::Changed(5));
+        assert!(!t.is_fresh());
+        assert_eq!(t.as_signal(), Signal::Unchanged);
+    }
+
+    #[test]
+    fn test_changes() {
+        let mut t = Tracked::new(1);
+
+        let changes: Vec<_> = t.changes(vec![1, 1, 2, 2, 2, 3, 1, 1]).collect();
+
+        assert_eq!(changes, vec![2, 3, 1]);
+        assert_eq!(t.peek(), 1);
+        assert!(t.is_fresh());
+    }
+
+    #[test]
+    fn test_changes_none() {
+        let mut t = Tracked::new(4);
+
+        // Mark as stale
+        t.get();
+
+        let changes: Vec<_> = t.changes(vec![4, 4, 4]).collect();
+
+        assert!(changes.is_empty());
+        assert!(!t.is_fresh());
+    }
+
     #[test]
     fn test_peek() {
         let t = Tracked::new(777);
@@ -133,11 +804,79 @@ mod tests {
         assert_eq!(v, 777);
     }
 
+    #[test]
+    fn test_generation() {
+        let mut t = Tracked::new(5);
+        let start = t.generation();
+
+        // Same value - generation unchanged
+        t.set(5);
+
+        assert!(!t.changed_since(start));
+        assert_eq!(t.generations_elapsed(start), 0);
+
+        t.set(6);
+        t.set(7);
+
+        assert!(t.changed_since(start));
+        assert_eq!(t.generations_elapsed(start), 2);
+        assert!(!t.changed_since(t.generation()));
+    }
+
+    #[test]
+    fn test_generations_elapsed_wrapping() {
+        let mut t = Tracked {
+            generation: u64::MAX - 1,
+            ..Tracked::new(5)
+        };
+        let start = t.generation();
+
+        // Wraps past zero
+        t.set(6);
+        t.set(7);
+        t.set(8);
+
+        assert_eq!(t.generation, 1);
+        assert_eq!(t.generations_elapsed(start), 3);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic]
+    fn test_generation_other_value() {
+        let a = Tracked::new(5);
+        let b = Tracked::new(5);
+
+        b.changed_since(a.generation());
+    }
+
+    #[test]
+    fn test_generation_copy() {
+        let mut a = Tracked::new(5);
+        let start = a.generation();
+        let b = a;
+
+        // Copies share identity
+        a.set(6);
+
+        assert!(a.changed_since(start));
+        assert!(!b.changed_since(start));
+    }
+
+    #[test]
+    fn test_debug() {
+        let mut t = Tracked::new(5);
+
+        t.get();
+
+        assert_eq!(format!("{:?}", t), "Tracked { val: 5, fresh: false }");
+    }
+
     #[test]
     fn test_is_fresh() {
         let t = Tracked {
             fresh: true,
-            val: 5
+            ..Tracked::new(5)
         };
 
         assert!(t.is_fresh());
@@ -147,9 +886,28 @@ mod tests {
     fn test_is_stale() {
         let t = Tracked {
             fresh: false,
-            val: 5
+            ..Tracked::new(5)
         };
 
         assert!(!t.is_fresh());
     }
+
+    #[test]
+    fn test_debug_assert_stale() {
+        let mut t = Tracked::new(5);
+
+        // Mark as stale
+        t.get();
+
+        t.debug_assert_stale();
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic]
+    fn test_debug_assert_stale_fresh() {
+        let t = Tracked::new(5);
+
+        t.debug_assert_stale();
+    }
 }
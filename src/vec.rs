@@ -0,0 +1,108 @@
+use reference::{Snapshot, TrackedRef};
+
+/// Vector of values, each tracking its own freshness.
+pub struct TrackedVec<T> {
+    items: Vec<TrackedRef<T>>
+}
+
+/// Values and per-element freshness of a `TrackedVec` captured by `TrackedVec::snapshot`.
+#[derive(Clone)]
+pub struct VecSnapshot<T> {
+    items: Vec<Snapshot<T>>
+}
+
+impl<T> TrackedVec<T> {
+    pub fn new() -> Self {
+        TrackedVec {
+            items: Vec::new()
+        }
+    }
+
+    /// Append a new value, marked as fresh.
+    pub fn push(&mut self, val: T) {
+        self.items.push(TrackedRef::new(val));
+    }
+
+    pub fn get(&self, index: usize) -> Option<&TrackedRef<T>> {
+        self.items.get(index)
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut TrackedRef<T>> {
+        self.items.get_mut(index)
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Restore every value and its freshness from `snap`, including the length.
+    pub fn restore(&mut self, snap: VecSnapshot<T>) {
+        self.items = snap.items.into_iter().map(TrackedRef::from).collect();
+    }
+}
+
+impl<T: Clone> TrackedVec<T> {
+    /// Capture every value and its freshness, without marking them.
+    pub fn snapshot(&self) -> VecSnapshot<T> {
+        VecSnapshot {
+            items: self.items.iter().map(|t| t.snapshot()).collect()
+        }
+    }
+}
+
+impl<T> Default for TrackedVec<T> {
+    fn default() -> Self {
+        TrackedVec::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reference::TrackedRefSet;
+
+    #[test]
+    fn test_push() {
+        let mut v = TrackedVec::new();
+
+        v.push(1);
+        v.push(2);
+
+        assert_eq!(v.len(), 2);
+        assert!(v.get(0).unwrap().is_fresh());
+        assert_eq!(v.get(1).unwrap().peek(), &2);
+        assert!(v.get(2).is_none());
+    }
+
+    #[test]
+    fn test_snapshot_restore() {
+        let mut v = TrackedVec::new();
+
+        v.push(1);
+        v.push(2);
+        v.push(3);
+
+        // Mark first two as stale
+        v.get_mut(0).unwrap().get();
+        v.get_mut(1).unwrap().get();
+
+        let snap = v.snapshot();
+
+        v.get_mut(0).unwrap().set(10);
+        v.get_mut(2).unwrap().get();
+        v.push(4);
+
+        v.restore(snap);
+
+        assert_eq!(v.len(), 3);
+        assert_eq!(v.get(0).unwrap().peek(), &1);
+        assert!(!v.get(0).unwrap().is_fresh());
+        assert!(!v.get(1).unwrap().is_fresh());
+        assert_eq!(v.get(2).unwrap().peek(), &3);
+        assert!(v.get(2).unwrap().is_fresh());
+    }
+}